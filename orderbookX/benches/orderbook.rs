@@ -0,0 +1,146 @@
+#![allow(non_snake_case)]
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use orderbookX::{
+    orderbook::{IdentifiableOrder, Order, OrderBook},
+    price::Price,
+    traits::matching_engine::{MatchingEngine, OrderKind},
+};
+
+const PRICE_LEVELS: [usize; 3] = [100, 1_000, 10_000];
+const ORDERS_PER_LEVEL: u64 = 4;
+const DEEP_LEVEL_ORDERS: u64 = 10_000;
+
+/// Ask side with the given amount of price levels at 1.00, 2.00, ...
+fn filled_order_book(price_levels: usize) -> OrderBook {
+    let mut order_book = OrderBook::default();
+    for level in 1..=price_levels {
+        for i in 0..ORDERS_PER_LEVEL {
+            let order_id = level as u64 * ORDERS_PER_LEVEL + i;
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(level, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
+        }
+    }
+    order_book
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert_new_price_level");
+    for price_levels in PRICE_LEVELS {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(price_levels),
+            &price_levels,
+            |b, &price_levels| {
+                b.iter_batched_ref(
+                    || filled_order_book(price_levels),
+                    |order_book| {
+                        // New price level in the middle of the book
+                        order_book
+                            .insert_order(Order::sell(
+                                Price::new(price_levels / 2, 50),
+                                IdentifiableOrder::new(0, 100),
+                            ))
+                            .unwrap()
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn cancel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cancel");
+    for price_levels in PRICE_LEVELS {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(price_levels),
+            &price_levels,
+            |b, &price_levels| {
+                b.iter_batched_ref(
+                    || filled_order_book(price_levels),
+                    // Last order in the FIFO queue of a price level in the middle of the book
+                    |order_book| {
+                        let order_id = (price_levels / 2) as u64 * ORDERS_PER_LEVEL + 3;
+                        order_book.cancel(order_id).unwrap()
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Ask side with all orders queued on a single price level at 1.00
+fn deep_level_order_book() -> OrderBook {
+    let mut order_book = OrderBook::default();
+    for order_id in 0..DEEP_LEVEL_ORDERS {
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(order_id, 100),
+            ))
+            .unwrap();
+    }
+    order_book
+}
+
+fn cancel_deep_level(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cancel_deep_level");
+    let positions = [
+        ("front", 0),
+        ("middle", DEEP_LEVEL_ORDERS / 2),
+        ("back", DEEP_LEVEL_ORDERS - 1),
+    ];
+    for (position, order_id) in positions {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(position),
+            &order_id,
+            |b, &order_id| {
+                b.iter_batched_ref(
+                    deep_level_order_book,
+                    |order_book| order_book.cancel(order_id).unwrap(),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn market_buy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("market_buy_ten_levels");
+    for price_levels in PRICE_LEVELS {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(price_levels),
+            &price_levels,
+            |b, &price_levels| {
+                b.iter_batched_ref(
+                    || filled_order_book(price_levels),
+                    // Sweeps the first ten price levels completely
+                    |order_book| {
+                        order_book
+                            .market(
+                                Order::buy(
+                                    Price::new(1, 0),
+                                    IdentifiableOrder::new(0, 10 * ORDERS_PER_LEVEL * 100),
+                                )
+                                .with_kind(OrderKind::Market),
+                            )
+                            .unwrap()
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, insert, cancel, cancel_deep_level, market_buy);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+use core::fmt;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Source of the timestamps used by the orderbook, in nanoseconds since the Unix epoch.
+///
+/// Injected into the orderbook so that tests can control the time.
+pub trait Clock: fmt::Debug {
+    fn now(&self) -> u64;
+}
+
+/// Wall clock time of the system
+#[derive(Default, Debug, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| {
+                u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+            })
+    }
+}
+
+/// Clock which only moves when told to, mostly useful for testing.
+///
+/// Clones share the same time, so a clone kept outside of the orderbook controls the clock of the orderbook.
+#[derive(Default, Debug, Clone)]
+pub struct ManualClock {
+    now: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new(now: u64) -> Self {
+        Self {
+            now: Arc::new(AtomicU64::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    /// Moves the clock forward by the given amount of nanoseconds
+    pub fn advance(&self, nanos: u64) {
+        self.now.fetch_add(nanos, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
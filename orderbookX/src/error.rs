@@ -0,0 +1,114 @@
+use core::fmt;
+use std::error::Error;
+
+use crate::price::{Notional, PriceError};
+
+/// Errors of all orderbook and matching engine operations.
+/// The orderbook is left unchanged if an operation returns an error.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BookError {
+    /// Order quantity has to be greater than zero
+    ZeroQuantity,
+    /// No resting order with the given order id exists
+    UnknownOrder(u64),
+    /// An order with the given order id is already resting in the orderbook
+    DuplicateOrderId(u64),
+    /// Price of the order is not valid
+    InvalidPrice(PriceError),
+    /// Inserting the order without matching would cross the spread
+    CrossedBook,
+    /// Order quantity is not a multiple of the lot size of the instrument
+    OffLot { qty: u64, lot_size: u64 },
+    /// Order quantity is below the minimum quantity of the instrument
+    BelowMinQuantity { qty: u64, min_qty: u64 },
+    /// Order quantity is above the maximum quantity of the instrument
+    AboveMaxQuantity { qty: u64, max_qty: u64 },
+    /// Value of the order is above the maximum notional of the instrument
+    AboveMaxNotional {
+        notional: Notional,
+        max_notional: Notional,
+    },
+    /// Stop orders need a stop kind and a stop price
+    InvalidStopOrder,
+    /// Orders of a group need distinct order ids and the exit orders of a bracket the opposite side of the entry
+    InvalidOrderGroup,
+    /// Expiry time of a good till date order has already passed
+    ExpiredOrder,
+    /// Order id sequence has no ids left
+    IdsExhausted,
+    /// Lot size of an instrument has to be greater than zero
+    ZeroLotSize,
+    /// Minimum quantity of an instrument is above its maximum quantity
+    InvalidQuantityLimits { min_qty: u64, max_qty: u64 },
+    /// Display quantity of an iceberg order has to be greater than zero and may not exceed its quantity
+    InvalidDisplayQuantity { display_qty: u64, qty: u64 },
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookError::ZeroQuantity => write!(f, "Order quantity must be greater than zero"),
+            BookError::UnknownOrder(order_id) => write!(f, "Unknown order id: {}", order_id),
+            BookError::DuplicateOrderId(order_id) => {
+                write!(f, "Order id already exists: {}", order_id)
+            }
+            BookError::InvalidPrice(error) => write!(f, "Invalid price: {}", error),
+            BookError::CrossedBook => write!(f, "Order would cross the spread"),
+            BookError::OffLot { qty, lot_size } => write!(
+                f,
+                "Order quantity {} is not a multiple of the lot size {}",
+                qty, lot_size
+            ),
+            BookError::BelowMinQuantity { qty, min_qty } => write!(
+                f,
+                "Order quantity {} is below the minimum quantity {}",
+                qty, min_qty
+            ),
+            BookError::AboveMaxQuantity { qty, max_qty } => write!(
+                f,
+                "Order quantity {} is above the maximum quantity {}",
+                qty, max_qty
+            ),
+            BookError::AboveMaxNotional {
+                notional,
+                max_notional,
+            } => write!(
+                f,
+                "Order notional {} is above the maximum notional {}",
+                notional, max_notional
+            ),
+            BookError::InvalidStopOrder => {
+                write!(f, "Stop orders need a stop kind and a stop price")
+            }
+            BookError::InvalidOrderGroup => write!(f, "Invalid order group"),
+            BookError::ExpiredOrder => write!(f, "Order has already expired"),
+            BookError::IdsExhausted => write!(f, "No order ids left"),
+            BookError::ZeroLotSize => write!(f, "Lot size must be greater than zero"),
+            BookError::InvalidQuantityLimits { min_qty, max_qty } => write!(
+                f,
+                "Minimum quantity {} is above the maximum quantity {}",
+                min_qty, max_qty
+            ),
+            BookError::InvalidDisplayQuantity { display_qty, qty } => write!(
+                f,
+                "Display quantity {} is not between 1 and the order quantity {}",
+                display_qty, qty
+            ),
+        }
+    }
+}
+
+impl Error for BookError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BookError::InvalidPrice(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<PriceError> for BookError {
+    fn from(error: PriceError) -> Self {
+        BookError::InvalidPrice(error)
+    }
+}
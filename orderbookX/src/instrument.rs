@@ -0,0 +1,287 @@
+use crate::{
+    error::BookError,
+    price::{Notional, Price, PriceError},
+};
+
+/// Trading rules of the instrument an orderbook is trading.
+///
+/// Defaults to two decimal places with a tick size of 0.01 and no quantity or notional limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstrumentRules {
+    // Number of decimal places of all prices
+    scale: u8,
+    // Every price has to be a multiple of the tick size
+    tick_size: Price,
+    // Every quantity has to be a multiple of the lot size
+    lot_size: u64,
+    min_qty: u64,
+    max_qty: u64,
+    // Maximum value of a single order
+    max_notional: Option<Notional>,
+}
+
+impl Default for InstrumentRules {
+    fn default() -> Self {
+        Self {
+            scale: 2,
+            tick_size: Price::new(0, 1),
+            lot_size: 1,
+            min_qty: 1,
+            max_qty: u64::MAX,
+            max_notional: None,
+        }
+    }
+}
+
+impl InstrumentRules {
+    /// Creates the rules for prices with `scale` decimal places in steps of `tick_size`,
+    /// e.g. `InstrumentRules::new(2, Price::new(0, 25))` for a 0.25 tick.
+    ///
+    /// The tick size has to be representable with the given scale.
+    pub fn new(scale: u8, tick_size: Price) -> Result<Self, PriceError> {
+        let tick_size = tick_size.rescale(scale)?;
+        Ok(Self {
+            scale,
+            tick_size,
+            ..Self::default()
+        })
+    }
+
+    /// Order quantities have to be a multiple of the lot size.
+    ///
+    /// A lot size of zero is rejected.
+    pub fn with_lot_size(mut self, lot_size: u64) -> Result<Self, BookError> {
+        if lot_size == 0 {
+            return Err(BookError::ZeroLotSize);
+        }
+        self.lot_size = lot_size;
+        Ok(self)
+    }
+
+    /// Smallest accepted order quantity, may not be above the maximum quantity
+    pub fn with_min_qty(mut self, min_qty: u64) -> Result<Self, BookError> {
+        self.min_qty = min_qty;
+        self.check_qty_limits()
+    }
+
+    /// Largest accepted order quantity, may not be below the minimum quantity
+    pub fn with_max_qty(mut self, max_qty: u64) -> Result<Self, BookError> {
+        self.max_qty = max_qty;
+        self.check_qty_limits()
+    }
+
+    fn check_qty_limits(self) -> Result<Self, BookError> {
+        if self.min_qty > self.max_qty {
+            return Err(BookError::InvalidQuantityLimits {
+                min_qty: self.min_qty,
+                max_qty: self.max_qty,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Largest accepted value (price * quantity) of a single order
+    pub fn with_max_notional(mut self, max_notional: Notional) -> Self {
+        self.max_notional = Some(max_notional);
+        self
+    }
+
+    pub fn get_scale(&self) -> u8 {
+        self.scale
+    }
+
+    pub fn get_tick_size(&self) -> &Price {
+        &self.tick_size
+    }
+
+    pub fn get_lot_size(&self) -> u64 {
+        self.lot_size
+    }
+
+    pub fn get_min_qty(&self) -> u64 {
+        self.min_qty
+    }
+
+    pub fn get_max_qty(&self) -> u64 {
+        self.max_qty
+    }
+
+    pub fn get_max_notional(&self) -> Option<Notional> {
+        self.max_notional
+    }
+
+    /// Validates the price against the scale and tick size of the instrument.
+    ///
+    /// Returns the price with the scale of the instrument, off-tick prices are rejected instead of rounded.
+    pub fn validate_price(&self, price: &Price) -> Result<Price, PriceError> {
+        let price = price.rescale(self.scale)?;
+        if !price.get_units().is_multiple_of(self.tick_size.get_units()) {
+            return Err(PriceError::OffTick);
+        }
+        Ok(price)
+    }
+
+    /// Validates the quantity against the lot size and the quantity limits of the instrument.
+    pub fn validate_qty(&self, qty: u64) -> Result<(), BookError> {
+        if !qty.is_multiple_of(self.lot_size) {
+            Err(BookError::OffLot {
+                qty,
+                lot_size: self.lot_size,
+            })
+        } else if qty < self.min_qty {
+            Err(BookError::BelowMinQuantity {
+                qty,
+                min_qty: self.min_qty,
+            })
+        } else if qty > self.max_qty {
+            Err(BookError::AboveMaxQuantity {
+                qty,
+                max_qty: self.max_qty,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Validates the value of an order against the maximum notional of the instrument.
+    pub fn validate_notional(&self, notional: Notional) -> Result<(), BookError> {
+        match self.max_notional {
+            Some(max_notional) if notional > max_notional => Err(BookError::AboveMaxNotional {
+                notional,
+                max_notional,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the price one tick above.
+    ///
+    /// Returns None if the price can not be represented anymore.
+    pub fn next_price(&self, price: &Price) -> Option<Price> {
+        price
+            .rescale(self.scale)
+            .ok()?
+            .checked_add_ticks(1, &self.tick_size)
+    }
+
+    /// Returns the price one tick below.
+    ///
+    /// Returns None if there is no positive price one tick below.
+    pub fn prev_price(&self, price: &Price) -> Option<Price> {
+        price
+            .rescale(self.scale)
+            .ok()?
+            .checked_sub_ticks(1, &self.tick_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules() {
+        let rules = InstrumentRules::default();
+        assert_eq!(
+            rules.validate_price(&Price::new(10, 5)),
+            Ok(Price::new(10, 5))
+        );
+        assert_eq!(
+            rules.validate_price(&Price::from_units(10055, 3).unwrap()),
+            Err(PriceError::TooPrecise { scale: 2 })
+        );
+    }
+
+    #[test]
+    fn test_quarter_tick() {
+        let rules = InstrumentRules::new(2, Price::new(0, 25)).unwrap();
+        assert_eq!(
+            rules.validate_price(&Price::new(10, 75)),
+            Ok(Price::new(10, 75))
+        );
+        assert_eq!(
+            rules.validate_price(&Price::new(10, 10)),
+            Err(PriceError::OffTick)
+        );
+        assert_eq!(
+            rules.next_price(&Price::new(10, 75)),
+            Some(Price::new(11, 0))
+        );
+        assert_eq!(
+            rules.prev_price(&Price::new(10, 0)),
+            Some(Price::new(9, 75))
+        );
+        assert_eq!(rules.prev_price(&Price::new(0, 25)), None);
+    }
+
+    #[test]
+    fn test_eight_decimals() {
+        let rules = InstrumentRules::new(8, Price::from_units(1, 8).unwrap()).unwrap();
+        let price = Price::from_units(2_500_000_001, 8).unwrap();
+        assert_eq!(rules.validate_price(&price), Ok(price.clone()));
+        // Lower scale prices are widened to the scale of the instrument
+        let validated = rules.validate_price(&Price::new(25, 0)).unwrap();
+        assert_eq!(validated.get_scale(), 8);
+        assert_eq!(validated.get_units(), 2_500_000_000);
+    }
+
+    #[test]
+    fn test_tick_size_exceeding_scale() {
+        assert_eq!(
+            InstrumentRules::new(1, Price::new(0, 25)),
+            Err(PriceError::TooPrecise { scale: 1 })
+        );
+        assert_eq!(
+            InstrumentRules::new(19, Price::new(0, 25)),
+            Err(PriceError::ScaleTooLarge(19))
+        );
+    }
+
+    #[test]
+    fn test_quantity_rules() {
+        let rules = InstrumentRules::default()
+            .with_min_qty(5)
+            .and_then(|rules| rules.with_max_qty(10))
+            .unwrap();
+        assert_eq!(rules.get_lot_size(), 1);
+        assert_eq!(rules.validate_qty(5), Ok(()));
+        assert_eq!(rules.validate_qty(10), Ok(()));
+        assert_eq!(
+            rules.validate_qty(4),
+            Err(BookError::BelowMinQuantity { qty: 4, min_qty: 5 })
+        );
+        assert_eq!(
+            rules.validate_qty(11),
+            Err(BookError::AboveMaxQuantity {
+                qty: 11,
+                max_qty: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_quantity_rules() {
+        assert_eq!(
+            InstrumentRules::default().with_lot_size(0),
+            Err(BookError::ZeroLotSize)
+        );
+        assert_eq!(
+            InstrumentRules::default()
+                .with_max_qty(10)
+                .and_then(|rules| rules.with_min_qty(11)),
+            Err(BookError::InvalidQuantityLimits {
+                min_qty: 11,
+                max_qty: 10
+            })
+        );
+        assert_eq!(
+            InstrumentRules::default()
+                .with_min_qty(5)
+                .and_then(|rules| rules.with_max_qty(4)),
+            Err(BookError::InvalidQuantityLimits {
+                min_qty: 5,
+                max_qty: 4
+            })
+        );
+    }
+}
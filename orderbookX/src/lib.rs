@@ -1,3 +1,9 @@
+#![allow(non_snake_case)]
+
+pub mod clock;
+pub mod error;
+pub mod instrument;
+pub mod market_data;
 pub mod orderbook;
 pub mod price;
 pub mod traits;
@@ -1,32 +1,27 @@
+#![allow(non_snake_case)]
+
 use orderbookX::{
+    error::BookError,
     orderbook::{IdentifiableOrder, Order, OrderBook},
     price::Price,
-    traits::matching_engine::{MatchingEngine, OrderType},
+    traits::matching_engine::MatchingEngine,
 };
 
-fn main() {
+fn main() -> Result<(), BookError> {
     println!("Short Showcase of Orderbook:");
     let mut order_book = OrderBook::default();
     println!("Empty Orderbook:\n{}", order_book);
-    order_book.insert_buy_order(Order::new(
+    order_book.insert_order(Order::buy(
         Price::new(1, 211),
         IdentifiableOrder::new(1, 50),
-    ));
-    order_book.insert_buy_order(Order::new(Price::new(1, 0), IdentifiableOrder::new(2, 50)));
-    order_book.insert_buy_order(Order::new(Price::new(2, 0), IdentifiableOrder::new(3, 50)));
+    ))?;
+    order_book.insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(2, 50)))?;
+    order_book.insert_order(Order::buy(Price::new(2, 0), IdentifiableOrder::new(3, 50)))?;
     println!("Orderbook After Buy Order Insertion:\n{}", order_book);
-    order_book.match_and_insert(
-        Order::new(Price::new(2, 0), IdentifiableOrder::new(4, 60)),
-        OrderType::Sell,
-    );
-    order_book.match_and_insert(
-        Order::new(Price::new(6, 0), IdentifiableOrder::new(4, 60)),
-        OrderType::Sell,
-    );
+    order_book.submit(Order::sell(Price::new(2, 0), IdentifiableOrder::new(4, 60)))?;
+    order_book.submit(Order::sell(Price::new(6, 0), IdentifiableOrder::new(5, 60)))?;
     println!("Orderbook After Sell Order Insertion:\n{}", order_book);
-    order_book.match_and_insert(
-        Order::new(Price::new(5, 0), IdentifiableOrder::new(4, 60)),
-        OrderType::Buy,
-    );
+    order_book.submit(Order::buy(Price::new(5, 0), IdentifiableOrder::new(6, 60)))?;
     println!("Orderbook After Buy Order Insertion:\n{}", order_book);
+    Ok(())
 }
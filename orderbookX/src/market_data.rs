@@ -0,0 +1,7 @@
+mod candles;
+mod l2_book;
+mod trade_tape;
+
+pub use candles::{BarInterval, Candle, CandleBuilder, ZeroInterval};
+pub use l2_book::{L2Book, SequenceGap};
+pub use trade_tape::{SessionStats, Trade, TradeTape};
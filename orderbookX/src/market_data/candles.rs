@@ -0,0 +1,319 @@
+use core::fmt;
+use std::{collections::VecDeque, error::Error};
+
+use super::trade_tape::Trade;
+use crate::price::Price;
+
+/// When a candle is closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BarInterval {
+    /// Fixed time buckets of the given amount of nanoseconds, aligned to the Unix epoch
+    Time(u64),
+    /// After the given amount of trades
+    Ticks(u64),
+    /// Once the traded quantity reaches the given volume, trades are not split across candles
+    Volume(u64),
+}
+
+impl BarInterval {
+    fn length(&self) -> u64 {
+        match *self {
+            BarInterval::Time(length)
+            | BarInterval::Ticks(length)
+            | BarInterval::Volume(length) => length,
+        }
+    }
+}
+
+/// Interval with a length of zero, its candles would never close
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ZeroInterval {
+    pub interval: BarInterval,
+}
+
+impl fmt::Display for ZeroInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bar interval {:?} has a length of zero", self.interval)
+    }
+}
+
+impl Error for ZeroInterval {}
+
+/// Open, high, low, close and volume of the trades of one interval
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Candle {
+    // Start of the time bucket or timestamp of the first trade for tick and volume bars
+    open_time: u64,
+    // Timestamp of the last trade
+    close_time: u64,
+    open: Price,
+    high: Price,
+    low: Price,
+    close: Price,
+    volume: u128,
+    trade_count: u64,
+}
+
+impl Candle {
+    fn open(open_time: u64, trade: &Trade) -> Self {
+        Self {
+            open_time,
+            close_time: trade.get_timestamp(),
+            open: trade.get_price().clone(),
+            high: trade.get_price().clone(),
+            low: trade.get_price().clone(),
+            close: trade.get_price().clone(),
+            volume: u128::from(trade.get_qty()),
+            trade_count: 1,
+        }
+    }
+
+    fn update(&mut self, trade: &Trade) {
+        let price = trade.get_price();
+        if price > &self.high {
+            self.high = price.clone();
+        }
+        if price < &self.low {
+            self.low = price.clone();
+        }
+        self.close = price.clone();
+        self.close_time = trade.get_timestamp();
+        self.volume += u128::from(trade.get_qty());
+        self.trade_count += 1;
+    }
+
+    pub fn get_open_time(&self) -> u64 {
+        self.open_time
+    }
+
+    pub fn get_close_time(&self) -> u64 {
+        self.close_time
+    }
+
+    pub fn get_open(&self) -> &Price {
+        &self.open
+    }
+
+    pub fn get_high(&self) -> &Price {
+        &self.high
+    }
+
+    pub fn get_low(&self) -> &Price {
+        &self.low
+    }
+
+    pub fn get_close(&self) -> &Price {
+        &self.close
+    }
+
+    pub fn get_volume(&self) -> u128 {
+        self.volume
+    }
+
+    pub fn get_trade_count(&self) -> u64 {
+        self.trade_count
+    }
+}
+
+/// Aggregates trades into candles of one interval.
+///
+/// Intervals without trades do not produce a candle.
+/// Only the most recent completed candles are kept, the oldest candle is dropped once the capacity is reached.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CandleBuilder {
+    interval: BarInterval,
+    // Candle of the running interval
+    current: Option<Candle>,
+    completed: VecDeque<Candle>,
+    capacity: usize,
+}
+
+impl CandleBuilder {
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Intervals with a length of zero are rejected.
+    pub fn new(interval: BarInterval) -> Result<Self, ZeroInterval> {
+        if interval.length() == 0 {
+            return Err(ZeroInterval { interval });
+        }
+        Ok(Self {
+            interval,
+            current: None,
+            completed: VecDeque::new(),
+            capacity: Self::DEFAULT_CAPACITY,
+        })
+    }
+
+    /// Changes the amount of kept completed candles, drops the oldest candles if more are kept than fit
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        while self.completed.len() > capacity {
+            self.completed.pop_front();
+        }
+        self
+    }
+
+    pub fn get_interval(&self) -> BarInterval {
+        self.interval
+    }
+
+    /// Candle of the running interval, it may still change
+    pub fn get_current(&self) -> Option<&Candle> {
+        self.current.as_ref()
+    }
+
+    /// Completed candles, oldest first
+    pub fn get_candles(&self) -> impl DoubleEndedIterator<Item = &Candle> {
+        self.completed.iter()
+    }
+
+    pub fn record(&mut self, trade: &Trade) {
+        let timestamp = trade.get_timestamp();
+        if let BarInterval::Time(_) = self.interval {
+            self.close_until(timestamp);
+        }
+        match self.current.as_mut() {
+            Some(candle) => candle.update(trade),
+            None => {
+                let open_time = match self.interval {
+                    BarInterval::Time(length) => timestamp - timestamp % length,
+                    BarInterval::Ticks(_) | BarInterval::Volume(_) => timestamp,
+                };
+                self.current = Some(Candle::open(open_time, trade));
+            }
+        }
+
+        let length = self.interval.length();
+        let complete = self
+            .current
+            .as_ref()
+            .is_some_and(|candle| match self.interval {
+                BarInterval::Time(_) => false,
+                BarInterval::Ticks(_) => candle.trade_count >= length,
+                BarInterval::Volume(_) => candle.volume >= u128::from(length),
+            });
+        if complete {
+            self.complete();
+        }
+    }
+
+    /// Completes the running time bar if its interval has ended before `now`.
+    ///
+    /// Time bars are otherwise only completed by the first trade of a later interval.
+    pub fn close_until(&mut self, now: u64) {
+        let BarInterval::Time(length) = self.interval else {
+            return;
+        };
+        let ended = self
+            .current
+            .as_ref()
+            .is_some_and(|candle| now.saturating_sub(candle.open_time) >= length);
+        if ended {
+            self.complete();
+        }
+    }
+
+    fn complete(&mut self) {
+        let Some(candle) = self.current.take() else {
+            return;
+        };
+        if self.capacity == 0 {
+            return;
+        }
+        if self.completed.len() == self.capacity {
+            self.completed.pop_front();
+        }
+        self.completed.push_back(candle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::matching_engine::OrderType;
+
+    fn trade(trade_id: u64, main_unit: usize, qty: u64, timestamp: u64) -> Trade {
+        Trade::new(
+            trade_id,
+            Price::new(main_unit, 0),
+            qty,
+            OrderType::Buy,
+            1,
+            2,
+            timestamp,
+        )
+    }
+
+    /// Time bars are aligned to their interval, intervals without trades are skipped
+    #[test]
+    fn test_time_bars() {
+        let mut builder = CandleBuilder::new(BarInterval::Time(100)).unwrap();
+        builder.record(&trade(1, 10, 5, 120));
+        builder.record(&trade(2, 12, 5, 150));
+        builder.record(&trade(3, 9, 5, 199));
+        builder.record(&trade(4, 11, 5, 420));
+        assert_eq!(
+            builder.get_candles().collect::<Vec<_>>(),
+            vec![&Candle {
+                open_time: 100,
+                close_time: 199,
+                open: Price::new(10, 0),
+                high: Price::new(12, 0),
+                low: Price::new(9, 0),
+                close: Price::new(9, 0),
+                volume: 15,
+                trade_count: 3,
+            }]
+        );
+        assert_eq!(builder.get_current().unwrap().get_open_time(), 400);
+
+        builder.close_until(499);
+        assert_eq!(builder.get_candles().count(), 1);
+        builder.close_until(500);
+        assert_eq!(builder.get_candles().count(), 2);
+        assert_eq!(builder.get_current(), None);
+    }
+
+    /// Tick bars close after a fixed amount of trades
+    #[test]
+    fn test_tick_bars() {
+        let mut builder = CandleBuilder::new(BarInterval::Ticks(2))
+            .unwrap()
+            .with_capacity(2);
+        for trade_id in 1..=7 {
+            builder.record(&trade(trade_id, trade_id as usize, 1, trade_id * 10));
+        }
+        let candles: Vec<_> = builder
+            .get_candles()
+            .map(|candle| (candle.get_open().clone(), candle.get_close().clone()))
+            .collect();
+        assert_eq!(
+            candles,
+            vec![
+                (Price::new(3, 0), Price::new(4, 0)),
+                (Price::new(5, 0), Price::new(6, 0)),
+            ]
+        );
+        assert_eq!(builder.get_current().unwrap().get_trade_count(), 1);
+    }
+
+    /// Volume bars close once the volume is reached without splitting the last trade
+    #[test]
+    fn test_volume_bars() {
+        let mut builder = CandleBuilder::new(BarInterval::Volume(100)).unwrap();
+        builder.record(&trade(1, 10, 60, 1));
+        builder.record(&trade(2, 11, 60, 2));
+        builder.record(&trade(3, 12, 100, 3));
+        let volumes: Vec<u128> = builder.get_candles().map(Candle::get_volume).collect();
+        assert_eq!(volumes, vec![120, 100]);
+        assert_eq!(builder.get_current(), None);
+        // Zero length intervals would never close
+        for interval in [
+            BarInterval::Time(0),
+            BarInterval::Ticks(0),
+            BarInterval::Volume(0),
+        ] {
+            assert_eq!(CandleBuilder::new(interval), Err(ZeroInterval { interval }));
+        }
+    }
+}
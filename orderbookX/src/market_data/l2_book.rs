@@ -0,0 +1,202 @@
+use core::fmt;
+use std::{collections::BTreeMap, error::Error};
+
+use crate::{
+    orderbook::{Depth, LevelUpdate, PriceLevel},
+    price::Price,
+    traits::matching_engine::OrderType,
+};
+
+/// Level update which does not directly follow the last applied update.
+/// At least one update has been missed, the view has to be rebuilt from a new snapshot.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SequenceGap {
+    pub expected: u64,
+    pub received: u64,
+}
+
+impl fmt::Display for SequenceGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Expected level update {} but received {}",
+            self.expected, self.received
+        )
+    }
+}
+
+impl Error for SequenceGap {}
+
+/// Consumer side level-2 view of an orderbook, built from a snapshot and kept up to date with level updates
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct L2Book {
+    // Sequence number of the last applied update
+    sequence: u64,
+    bids: BTreeMap<Price, PriceLevel>,
+    asks: BTreeMap<Price, PriceLevel>,
+}
+
+impl L2Book {
+    /// Builds the view from a snapshot, which has to contain every price level, e.g. `depth(usize::MAX)`
+    pub fn from_depth(depth: &Depth) -> Self {
+        let by_price = |levels: &[PriceLevel]| {
+            levels
+                .iter()
+                .map(|level| (level.get_price().clone(), level.clone()))
+                .collect()
+        };
+        Self {
+            sequence: depth.get_sequence(),
+            bids: by_price(depth.get_bids()),
+            asks: by_price(depth.get_asks()),
+        }
+    }
+
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Applies the next level update.
+    ///
+    /// Updates already included in the view are ignored, e.g. updates buffered while the snapshot was taken.
+    /// Returns an error and leaves the view unchanged if an update has been missed.
+    pub fn apply(&mut self, update: &LevelUpdate) -> Result<(), SequenceGap> {
+        let received = update.get_sequence();
+        if received <= self.sequence {
+            return Ok(());
+        }
+        let expected = self.sequence + 1;
+        if received != expected {
+            return Err(SequenceGap { expected, received });
+        }
+
+        let levels = match update.get_order_type() {
+            OrderType::Buy => &mut self.bids,
+            OrderType::Sell => &mut self.asks,
+        };
+        let level = update.get_level();
+        if update.is_removed() {
+            levels.remove(level.get_price());
+        } else {
+            levels.insert(level.get_price().clone(), level.clone());
+        }
+        self.sequence = received;
+        Ok(())
+    }
+
+    /// Best `levels` price levels per side, best price first, same as [crate::orderbook::OrderBook::depth]
+    pub fn depth(&self, levels: usize) -> Depth {
+        Depth::new(
+            self.sequence,
+            self.bids.values().rev().take(levels).cloned().collect(),
+            self.asks.values().take(levels).cloned().collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        orderbook::{IdentifiableOrder, Order, OrderBook},
+        traits::{
+            event_listener::{BookEvent, EventCollector},
+            matching_engine::MatchingEngine,
+        },
+    };
+
+    fn level_updates(order_book: &mut OrderBook<EventCollector>) -> Vec<LevelUpdate> {
+        order_book
+            .get_listener_mut()
+            .take_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                BookEvent::LevelUpdated(update) => Some(update),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Snapshot plus level updates result in the same view as the orderbook
+    #[test]
+    fn test_rebuild_from_snapshot_and_updates() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        for (order_id, price) in [(1, 1), (2, 2), (3, 2)] {
+            order_book
+                .insert_order(Order::buy(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(price + 10, 0),
+                    IdentifiableOrder::new(order_id + 10, 100),
+                ))
+                .unwrap();
+        }
+        let mut l2_book = L2Book::from_depth(&order_book.depth(usize::MAX));
+        // Updates fired before the snapshot are already part of it
+        let stale = level_updates(&mut order_book);
+        assert_eq!(stale.len(), 6);
+
+        // Partial fill, full level sweep, cancel, amend in place and amend to a new price
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(20, 150),
+            ))
+            .unwrap();
+        order_book
+            .match_and_insert(Order::buy(
+                Price::new(11, 0),
+                IdentifiableOrder::new(21, 150),
+            ))
+            .unwrap();
+        order_book.cancel(13).unwrap();
+        order_book.amend(3, 20, Price::new(2, 0)).unwrap();
+        order_book.amend(1, 100, Price::new(1, 50)).unwrap();
+        order_book.remove_ask_price_level(&Price::new(12, 0));
+
+        for update in stale.iter().chain(level_updates(&mut order_book).iter()) {
+            l2_book.apply(update).unwrap();
+        }
+        assert_eq!(l2_book.get_sequence(), order_book.get_sequence());
+        assert_eq!(l2_book.depth(usize::MAX), order_book.depth(usize::MAX));
+        assert_eq!(l2_book.depth(1), order_book.depth(1));
+    }
+
+    /// Missed level updates are detected and leave the view unchanged
+    #[test]
+    fn test_sequence_gap() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        let mut l2_book = L2Book::from_depth(&order_book.depth(usize::MAX));
+        for order_id in 1..=3 {
+            order_book
+                .insert_order(Order::buy(
+                    Price::new(order_id, 0),
+                    IdentifiableOrder::new(order_id as u64, 100),
+                ))
+                .unwrap();
+        }
+        let updates = level_updates(&mut order_book);
+        assert_eq!(
+            updates
+                .iter()
+                .map(LevelUpdate::get_sequence)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        l2_book.apply(&updates[0]).unwrap();
+        assert_eq!(
+            l2_book.apply(&updates[2]),
+            Err(SequenceGap {
+                expected: 2,
+                received: 3
+            })
+        );
+        assert_eq!(l2_book.get_sequence(), 1);
+        assert_eq!(l2_book.depth(usize::MAX).get_bids().len(), 1);
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+
+use crate::{
+    price::{Notional, Price},
+    traits::matching_engine::OrderType,
+};
+
+/// Single execution between a resting maker order and an incoming taker order
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Trade {
+    // Monotonically increasing per orderbook, starts at 1
+    trade_id: u64,
+    price: Price,
+    qty: u64,
+    // Side of the taker order
+    aggressor: OrderType,
+    maker_id: u64,
+    taker_id: u64,
+    // Nanoseconds since the Unix epoch
+    timestamp: u64,
+}
+
+impl Trade {
+    pub fn new(
+        trade_id: u64,
+        price: Price,
+        qty: u64,
+        aggressor: OrderType,
+        maker_id: u64,
+        taker_id: u64,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            trade_id,
+            price,
+            qty,
+            aggressor,
+            maker_id,
+            taker_id,
+            timestamp,
+        }
+    }
+
+    pub fn get_trade_id(&self) -> u64 {
+        self.trade_id
+    }
+
+    pub fn get_price(&self) -> &Price {
+        &self.price
+    }
+
+    pub fn get_qty(&self) -> u64 {
+        self.qty
+    }
+
+    pub fn get_aggressor(&self) -> OrderType {
+        self.aggressor
+    }
+
+    pub fn get_maker_id(&self) -> u64 {
+        self.maker_id
+    }
+
+    pub fn get_taker_id(&self) -> u64 {
+        self.taker_id
+    }
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+/// Ring buffer of the most recent trades, the oldest trade is dropped once the capacity is reached
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TradeTape {
+    trades: VecDeque<Trade>,
+    capacity: usize,
+}
+
+impl Default for TradeTape {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl TradeTape {
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            trades: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the capacity, drops the oldest trades if more trades are kept than fit
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.trades.len() > capacity {
+            self.trades.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, trade: Trade) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.trades.len() == self.capacity {
+            self.trades.pop_front();
+        }
+        self.trades.push_back(trade);
+    }
+
+    /// Kept trades, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Trade> {
+        self.trades.iter()
+    }
+
+    /// Most recent trade
+    pub fn last(&self) -> Option<&Trade> {
+        self.trades.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.trades.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trades.is_empty()
+    }
+}
+
+/// Running statistics of all trades of the current session
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct SessionStats {
+    last_price: Option<Price>,
+    high: Option<Price>,
+    low: Option<Price>,
+    volume: u128,
+    // Sum of price * quantity of all trades, None once it overflows
+    notional: Option<Notional>,
+    trade_count: u64,
+}
+
+impl SessionStats {
+    pub fn record(&mut self, trade: &Trade) {
+        let price = trade.get_price();
+        if self.high.as_ref().is_none_or(|high| price > high) {
+            self.high = Some(price.clone());
+        }
+        if self.low.as_ref().is_none_or(|low| price < low) {
+            self.low = Some(price.clone());
+        }
+        self.notional = if self.trade_count == 0 {
+            price.checked_mul_qty(trade.get_qty()).ok()
+        } else {
+            self.notional.and_then(|notional| {
+                notional.checked_add(&price.checked_mul_qty(trade.get_qty()).ok()?)
+            })
+        };
+        self.last_price = Some(price.clone());
+        self.volume += u128::from(trade.get_qty());
+        self.trade_count += 1;
+    }
+
+    pub fn get_last_price(&self) -> Option<&Price> {
+        self.last_price.as_ref()
+    }
+
+    pub fn get_high(&self) -> Option<&Price> {
+        self.high.as_ref()
+    }
+
+    pub fn get_low(&self) -> Option<&Price> {
+        self.low.as_ref()
+    }
+
+    /// Traded quantity
+    pub fn get_volume(&self) -> u128 {
+        self.volume
+    }
+
+    /// Traded value, None if there are no trades or the sum overflowed
+    pub fn get_notional(&self) -> Option<Notional> {
+        self.notional
+    }
+
+    pub fn get_trade_count(&self) -> u64 {
+        self.trade_count
+    }
+
+    /// Volume weighted average price, for display and analytics only
+    pub fn vwap(&self) -> Option<f64> {
+        if self.volume == 0 {
+            return None;
+        }
+        Some(self.notional?.to_f64() / self.volume as f64)
+    }
+}
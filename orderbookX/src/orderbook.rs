@@ -1,854 +1,4494 @@
+mod depth;
+mod execution_report;
 mod identifiable_order;
+mod order_id_generator;
+mod order_index;
+mod order_links;
+mod order_queue;
 mod orders;
+mod snapshot;
+mod stop_book;
 use core::fmt;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
 
+pub use depth::{Depth, LevelUpdate, PriceLevel};
+pub use execution_report::{ExecutionReport, Fill};
 pub use identifiable_order::IdentifiableOrder;
-use indexmap::IndexMap;
+pub use order_id_generator::OrderIdGenerator;
+pub use order_index::CancelledOrder;
 pub use orders::Order;
+pub use snapshot::{RestingOrder, Snapshot};
 use tracing::debug;
 
-use self::orders::OrderList;
+use self::{
+    order_index::{OrderIndex, OrderLocation},
+    order_links::{OrderLink, OrderLinks},
+    order_queue::OrderQueue,
+    orders::{OrderList, Orders},
+    stop_book::StopBook,
+};
 use crate::{
+    clock::{Clock, SystemClock},
+    error::BookError,
+    instrument::InstrumentRules,
+    market_data::{BarInterval, CandleBuilder, SessionStats, Trade, TradeTape},
     price::Price,
-    traits::matching_engine::{MatchingEngine, OrderType},
+    traits::{
+        event_listener::{BookEvent, EventListener, NoopListener},
+        matching_engine::{
+            MatchingEngine, OcoTrigger, OrderKind, OrderType, PostOnlyMode, PostOnlyOutcome,
+            TimeInForce,
+        },
+    },
 };
 
-#[derive(Default, Debug)]
-pub struct OrderBook {
+#[derive(Debug)]
+pub struct OrderBook<L: EventListener = NoopListener> {
     bids: OrderList,
     asks: OrderList,
+    // id -> location of every resting order
+    order_index: OrderIndex,
+    // Stop orders waiting for the last traded price to reach their stop price
+    stops: StopBook,
+    // OCO pairs and bracket entries by order id
+    links: OrderLinks,
+    // Take profit and stop loss orders of filled bracket entries, submitted once the current match is done
+    activated_brackets: VecDeque<(Order, Order)>,
+    // Set while triggered orders are executed, so that cascading triggers are handled by a single loop
+    triggering: bool,
+    order_ids: OrderIdGenerator,
+    rules: InstrumentRules,
+    // Price of the most recent fill
+    last_traded_price: Option<Price>,
+    // Sequence number of the last level update
+    sequence: u64,
+    trade_tape: TradeTape,
+    session_stats: SessionStats,
+    last_trade_id: u64,
+    // (expiry time, order id) of every order with an expiry, entries of orders which are no longer open are skipped by the sweep
+    expiries: BTreeSet<(u64, u64)>,
+    // End of the trading session in nanoseconds since the Unix epoch, the next UTC midnight if not set
+    session_end: Option<u64>,
+    // One builder per configured interval
+    candle_builders: Vec<CandleBuilder>,
+    clock: Box<dyn Clock>,
+    listener: L,
 }
 
-impl fmt::Display for OrderBook {
+impl<L: EventListener> fmt::Display for OrderBook<L> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Buy:\n{}\nSell:\n{}", self.bids, self.asks)
     }
 }
 
+impl Default for OrderBook {
+    fn default() -> Self {
+        Self::with_listener(NoopListener)
+    }
+}
+
 impl OrderBook {
-    fn new(bids: OrderList, asks: OrderList) -> Self {
+    pub fn new(bids: OrderList, asks: OrderList) -> Self {
+        let mut order_index = OrderIndex::new();
+        for (order_type, order_list) in [(OrderType::Buy, &bids), (OrderType::Sell, &asks)] {
+            for (price, orders) in order_list.order_list.iter() {
+                for (handle, order) in orders.iter_handles() {
+                    order_index.insert(
+                        order.get_id(),
+                        OrderLocation::new(order_type, price.clone(), handle),
+                    );
+                }
+            }
+        }
         Self {
             bids,
             asks,
+            order_index,
+            stops: StopBook::default(),
+            links: OrderLinks::new(),
+            activated_brackets: VecDeque::new(),
+            triggering: false,
+            order_ids: OrderIdGenerator::default(),
+            rules: InstrumentRules::default(),
+            last_traded_price: None,
+            sequence: 0,
+            trade_tape: TradeTape::default(),
+            session_stats: SessionStats::default(),
+            last_trade_id: 0,
+            expiries: BTreeSet::new(),
+            session_end: None,
+            candle_builders: Vec::new(),
+            clock: Box::new(SystemClock),
+            listener: NoopListener,
         }
     }
+
+    /// Creates an empty orderbook for an instrument with the given price rules
+    pub fn with_rules(rules: InstrumentRules) -> Self {
+        Self::with_listener_and_rules(NoopListener, rules)
+    }
 }
 
-impl OrderBook {
+impl<L: EventListener> OrderBook<L> {
+    /// Creates an empty orderbook, which passes all events to the given listener
+    pub fn with_listener(listener: L) -> Self {
+        Self::with_listener_and_rules(listener, InstrumentRules::default())
+    }
+
+    /// Creates an empty orderbook for an instrument with the given price rules,
+    /// which passes all events to the given listener
+    pub fn with_listener_and_rules(listener: L, rules: InstrumentRules) -> Self {
+        Self {
+            bids: OrderList::default(),
+            asks: OrderList::default(),
+            order_index: OrderIndex::new(),
+            stops: StopBook::default(),
+            links: OrderLinks::new(),
+            activated_brackets: VecDeque::new(),
+            triggering: false,
+            order_ids: OrderIdGenerator::default(),
+            rules,
+            last_traded_price: None,
+            sequence: 0,
+            trade_tape: TradeTape::default(),
+            session_stats: SessionStats::default(),
+            last_trade_id: 0,
+            expiries: BTreeSet::new(),
+            session_end: None,
+            candle_builders: Vec::new(),
+            clock: Box::new(SystemClock),
+            listener,
+        }
+    }
+
+    pub fn get_rules(&self) -> &InstrumentRules {
+        &self.rules
+    }
+
+    pub fn get_listener(&self) -> &L {
+        &self.listener
+    }
+
+    pub fn get_listener_mut(&mut self) -> &mut L {
+        &mut self.listener
+    }
+
+    fn emit(&mut self, event: BookEvent) {
+        self.listener.on_event(event);
+    }
+
+    /// Emits the current state of a price level with the next sequence number
+    fn emit_level(&mut self, order_type: OrderType, price: &Price) {
+        let order_list = match order_type {
+            OrderType::Buy => &self.bids.order_list,
+            OrderType::Sell => &self.asks.order_list,
+        };
+        let level = order_list.get_key_value(price).map_or_else(
+            || PriceLevel::new(price.clone(), 0, 0),
+            PriceLevel::aggregate,
+        );
+        self.sequence += 1;
+        self.emit(BookEvent::LevelUpdated(LevelUpdate::new(
+            self.sequence,
+            order_type,
+            level,
+        )));
+    }
+
+    /// Sequence number of the last level update, zero if the orderbook has never changed
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Replaces the clock used for all timestamps, defaults to the [SystemClock]
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Sets the end of the trading session, at which all Day orders expire
+    pub fn set_session_end(&mut self, session_end: u64) {
+        self.session_end = Some(session_end);
+    }
+
+    /// End of the trading session, defaults to the next UTC midnight according to the clock of the orderbook
+    pub fn get_session_end(&self) -> u64 {
+        self.session_end.unwrap_or_else(|| {
+            const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+            let now = self.clock.now();
+            (now - now % NANOS_PER_DAY).saturating_add(NANOS_PER_DAY)
+        })
+    }
+
+    /// Replaces the sequence used by [OrderBook::next_order_id]
+    pub fn set_order_id_generator(&mut self, order_ids: OrderIdGenerator) {
+        self.order_ids = order_ids;
+    }
+
+    /// Assigns an order id for clients which do not supply their own.
+    ///
+    /// Ids are taken from a monotonic sequence, ids of currently resting orders are skipped.
+    /// Fails once the sequence has no ids left.
+    pub fn next_order_id(&mut self) -> Result<u64, BookError> {
+        loop {
+            let order_id = self.order_ids.next_id().ok_or(BookError::IdsExhausted)?;
+            if !self.order_index.contains_key(&order_id) && !self.stops.contains(order_id) {
+                return Ok(order_id);
+            }
+        }
+    }
+}
+
+impl<L: EventListener> OrderBook<L> {
     /// Returns current market price
     /// Current market price is defined as the highest bid price currently in the orderbook.
     ///
     /// If not bids exist, then the price is the lowest ask price in the orderbook.
     pub fn get_price(&self) -> Option<&Price> {
-        if let Some(price) = self
-            .bids
-            .order_list
-            .last()
-            .map(|highest_bids| highest_bids.0)
-        {
+        if let Some(price) = self.bids.last().map(|highest_bids| highest_bids.0) {
             Some(price)
         } else {
-            self.asks
-                .order_list
-                .first()
-                .map(|lowest_asks| lowest_asks.0)
+            self.asks.first().map(|lowest_asks| lowest_asks.0)
         }
     }
 
-    fn highest_bid(&self) -> Option<(&Price, &IdentifiableOrder)> {
-        // get highest bid from buy side
-        if let Some((price, orders)) = self.bids.order_list.last() {
-            // we can unwrap here as the first last call is already ensuring to have an element here?
-            Some((price, orders.front().unwrap()))
-        } else {
-            None
-        }
+    /// Highest bid price level with its aggregated quantity
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids.last().map(PriceLevel::aggregate)
     }
 
-    fn highest_bids_mut(&mut self) -> Option<(&Price, &mut VecDeque<IdentifiableOrder>)> {
-        // get highest bidders from buy side
-        self.bids.order_list.last_mut()
+    /// Lowest ask price level with its aggregated quantity
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks.first().map(PriceLevel::aggregate)
     }
 
-    fn lowest_ask(&self) -> Option<(&Price, &IdentifiableOrder)> {
-        // get lowest ask price from sell side
-        if let Some((price, orders)) = self.asks.order_list.first() {
-            Some((price, orders.front().unwrap()))
-        } else {
-            None
-        }
+    /// Difference between the best ask and the best bid
+    ///
+    /// Returns None if one side is empty.
+    pub fn spread(&self) -> Option<Price> {
+        let (highest_bid, _) = self.bids.last()?;
+        let (lowest_ask, _) = self.asks.first()?;
+        lowest_ask.checked_sub(highest_bid)
     }
 
-    fn lowest_asks_mut(&mut self) -> Option<(&Price, &mut VecDeque<IdentifiableOrder>)> {
-        // get lowest ask price from sell side
-        self.asks.order_list.first_mut()
+    /// Price halfway between the best bid and the best ask, with one more decimal place if needed
+    ///
+    /// Returns None if one side is empty.
+    pub fn mid_price(&self) -> Option<Price> {
+        let (highest_bid, _) = self.bids.last()?;
+        let (lowest_ask, _) = self.asks.first()?;
+        highest_bid.checked_midpoint(lowest_ask)
     }
 
-    /// Insert Limit Buy Order
-    pub fn insert_buy_order(&mut self, insert_order: Order) {
-        let order_list = &mut self.bids;
-        // Insert Limit Order
-        order_list.insert_order(insert_order)
+    /// Mid price weighted by the quantity of the opposite best level, for analytics only.
+    ///
+    /// Moves towards the ask if the bid level holds more quantity and vice versa.
+    /// Returns None if one side is empty.
+    pub fn microprice(&self) -> Option<f64> {
+        let best_bid = self.best_bid()?;
+        let best_ask = self.best_ask()?;
+        let bid_qty = best_bid.get_qty() as f64;
+        let ask_qty = best_ask.get_qty() as f64;
+        Some(
+            (best_bid.get_price().to_f64() * ask_qty + best_ask.get_price().to_f64() * bid_qty)
+                / (bid_qty + ask_qty),
+        )
     }
 
-    /// Insert Limit Sell Order
-    pub fn insert_sell_order(&mut self, insert_order: Order) {
-        let order_list = &mut self.asks;
-        // Insert Limit Order
-        order_list.insert_order(insert_order)
+    /// Price of the most recent fill, unlike [OrderBook::get_price] it does not depend on resting orders
+    pub fn get_last_traded_price(&self) -> Option<&Price> {
+        self.last_traded_price.as_ref()
     }
 
-    pub fn remove_buy_order(&mut self, remove_order: Order) {
-        let order_book = &mut self.bids.order_list;
-        Self::remove_order(remove_order, order_book)
+    /// Most recent trades, oldest first
+    pub fn get_trade_tape(&self) -> &TradeTape {
+        &self.trade_tape
     }
 
-    pub fn remove_sell_order(&mut self, remove_order: Order) {
-        let order_book = &mut self.asks.order_list;
-        Self::remove_order(remove_order, order_book)
+    /// Changes the amount of kept trades, see [TradeTape::set_capacity]
+    pub fn set_trade_tape_capacity(&mut self, capacity: usize) {
+        self.trade_tape.set_capacity(capacity);
     }
 
-    pub fn remove_ask_price_level(&mut self, key: &Price) -> Option<VecDeque<IdentifiableOrder>> {
-        self.asks.order_list.shift_remove(key) // O(n)
+    pub fn get_session_stats(&self) -> &SessionStats {
+        &self.session_stats
     }
 
-    pub fn remove_bid_price_level(&mut self, key: &Price) -> Option<VecDeque<IdentifiableOrder>> {
-        self.bids.order_list.shift_remove(key) // O(n)
+    /// Starts a new session, the trade tape and the last traded price are kept
+    pub fn reset_session_stats(&mut self) {
+        self.session_stats = SessionStats::default();
     }
 
-    /// Order Modification: Remove/Cancel an Order
-    pub fn remove_order(
-        remove_order: Order,
-        order_book: &mut IndexMap<Price, VecDeque<IdentifiableOrder>>,
-    ) {
-        if let Some(orders_on_price_level) = order_book.get(remove_order.get_price()) {
-            // If the first statement is wrong, the second never gets executed.
-            // If the first statement is correct, the second statement never panics.
-            if orders_on_price_level.len() == 1
-                && orders_on_price_level.front().unwrap() == remove_order.get_order()
-            {
-                // If there is only one entry, we can delete the whole indexmap entry
-                order_book.remove_entry(remove_order.get_price());
-            } else {
-                //orders_on_price_level.remove(index)
-            }
+    /// Aggregates all following trades into candles of the builder's interval.
+    ///
+    /// Replaces an existing builder with the same interval.
+    pub fn add_candle_builder(&mut self, builder: CandleBuilder) {
+        let interval = builder.get_interval();
+        match self
+            .candle_builders
+            .iter_mut()
+            .find(|existing| existing.get_interval() == interval)
+        {
+            Some(existing) => *existing = builder,
+            None => self.candle_builders.push(builder),
         }
+    }
 
-        if let Some(orders_on_price_level) = order_book.get_mut(remove_order.get_price()) {
-            for (i, order) in orders_on_price_level.iter().enumerate() {
-                if order == remove_order.get_order() {
-                    // Multiple orders on that level, only delete the relevant entry
-                    orders_on_price_level.remove(i);
-                    break;
-                }
-            }
+    pub fn get_candle_builder(&self, interval: BarInterval) -> Option<&CandleBuilder> {
+        self.candle_builders
+            .iter()
+            .find(|builder| builder.get_interval() == interval)
+    }
+
+    /// Completes all time bars whose interval has ended according to the clock of the orderbook
+    pub fn close_candles(&mut self) {
+        let now = self.clock.now();
+        for builder in self.candle_builders.iter_mut() {
+            builder.close_until(now);
         }
     }
-}
 
-impl MatchingEngine for OrderBook {
-    fn market_buy_until(&mut self, mut buy_order: Order) -> (bool, u64, u64, Order) {
-        // Market buy order quantity
-        let market_buy_qty = buy_order.get_order().get_qty();
+    /// Buy stop orders waiting for their stop price, in trigger order
+    pub fn iter_buy_stops(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.stops.iter_buys()
+    }
 
-        // Get first sell orders to match
-        println!("{:?}", self.lowest_asks_mut());
-        let Some(mut price_level) = self.lowest_asks_mut() else {
-            // Orderbook is empty, nothing to match, order stays the same
-            return (true, market_buy_qty, 0, buy_order);
-        };
-        // Market price level right now
-        let mut market_price = price_level.0.clone();
-
-        // Check until
-        if buy_order.get_price() < &market_price {
-            // buy order price is lower than any asks, nothing to match, order stays the same
-            return (true, market_buy_qty, 0, buy_order);
-        }
-
-        // Orders at price level
-        let mut orders = price_level.1;
-
-        println!("Market buy order quantity: {}", market_buy_qty);
-
-        // Accumulates the qty until it reaches the orders amount or reaches buy_order price
-        let mut accumulator: u64 = 0;
-        while accumulator < market_buy_qty && &market_price <= buy_order.get_price() {
-            if let Some(matching_candidate) = orders.front() {
-                accumulator += matching_candidate.get_qty();
-                // Settle execution
-                if accumulator <= market_buy_qty {
-                    // We can remove matched order from the orderbook
-                    let order_to_remove = orders.pop_front().unwrap();
-                    // This is a rare case, this should be handled differently instead of branching unnecessarily in 99% of the cases
-                    if accumulator == market_buy_qty && orders.is_empty() {
-                        // No orders left at the given price
-                        // Remove price level from orderbook
-                        let _ = self.remove_ask_price_level(&market_price).unwrap();
-                        // We are done here
-                        break;
-                    }
-                    // Fire Event
-                    println!("Order to remove: {}", order_to_remove);
-                } else {
-                    // We need to reduce matched order in the orderbook (partial fill)
-                    let order_to_reduce = orders.front_mut().unwrap();
-                    // remaining unfilled amount on last maker limit order
-                    let remainder = accumulator - market_buy_qty;
-                    // Only reduce FIFO Queue Orders
-                    println!("Order to reduce: {}", order_to_reduce);
-                    order_to_reduce.set_qty(remainder);
-                    // Set accumulator to final filled amount
-                    accumulator -= remainder;
-                    // We can break since we are finished here
-                    println!("Unfilled amount of last limit sell order: {}", remainder);
-                    break;
-                }
-                // Go to next element on same price level
-            } else {
-                // No orders left at the given price, go to a higher price level
-                // 1. Remove price level from orderbook
-                let _ = self.remove_ask_price_level(&market_price).unwrap();
-                println!("Removed Price Level: {}", market_price);
-                // 2. Update to next price level
-                if let Some(next_matches) = self.lowest_asks_mut() {
-                    // Update Matches
-                    price_level = next_matches;
-                    // Update Market Price
-                    market_price = price_level.0.clone();
-                    // Update Orders
-                    orders = price_level.1;
-                } else {
-                    // Orderbook is empty
-                    break;
-                    // Unusual Outcome:
-                    // All orders are removed and the orderbook is empty
-                    // Limit buy is only partially executed
-                }
-            };
-        }
-        // Reduce order by filled amount
-        buy_order
-            .get_order_mut()
-            .set_qty(market_buy_qty - accumulator);
-        // Usual Outcome:
-        // All orders are removed including indexmap price levels if they are completely filled
-        // The last remaining order in the FIFO queue of the given price level was either exactly equal and was completely filled or only partially filled
-        (true, market_buy_qty, accumulator, buy_order)
+    /// Sell stop orders waiting for their stop price, in trigger order
+    pub fn iter_sell_stops(&self) -> impl Iterator<Item = &Order> + '_ {
+        self.stops.iter_sells()
     }
 
-    fn market_sell_until(&mut self, mut sell_order: Order) -> (bool, u64, u64, Order) {
-        let market_sell_qty = sell_order.get_order().get_qty();
+    /// Level-2 snapshot of the best `levels` price levels per side, best price first.
+    ///
+    /// Only walks the returned price levels, the rest of the orderbook is not touched.
+    pub fn depth(&self, levels: usize) -> Depth {
+        Depth::new(
+            self.sequence,
+            self.bids
+                .order_list
+                .iter()
+                .rev()
+                .take(levels)
+                .map(PriceLevel::aggregate)
+                .collect(),
+            self.asks
+                .order_list
+                .iter()
+                .take(levels)
+                .map(PriceLevel::aggregate)
+                .collect(),
+        )
+    }
 
-        // Get first sell orders to match
-        println!("{:?}", self.highest_bids_mut());
-        let Some(mut price_level) = self.highest_bids_mut() else {
-            // Orderbook is empty, nothing to match, order stays the same
-            return (true, market_sell_qty, 0, sell_order);
-        };
-        // Market price level right now
-        let mut market_price = price_level.0.clone();
-
-        // Check until
-        if sell_order.get_price() > &market_price {
-            // sell order price is higher than any bids, nothing to match, order stays the same
-            return (true, market_sell_qty, 0, sell_order);
-        }
-
-        let mut orders = price_level.1;
-
-        println!("Market buy order quantity: {}", market_sell_qty);
-
-        // Accumulates the qty until it reaches the orders amount or reaches sell_order price
-        let mut accumulator: u64 = 0;
-        while accumulator < market_sell_qty && sell_order.get_price() <= &market_price {
-            if let Some(matching_candidate) = orders.front() {
-                accumulator += matching_candidate.get_qty();
-                // Settle execution
-                if accumulator <= market_sell_qty {
-                    // We can remove matched order from the orderbook
-                    let order_to_remove = orders.pop_front().unwrap();
-                    // This is a rare case, this should be handled differently instead of branching unnecessarily in 99% of the cases
-                    if accumulator == market_sell_qty && orders.is_empty() {
-                        // No orders left at the given price
-                        // Remove price level from orderbook
-                        let _ = self.remove_bid_price_level(&market_price).unwrap();
-                        break;
-                    }
-                    // Fire Event
-                    println!("Order to remove: {}", order_to_remove);
-                } else {
-                    // We need to reduce matched order in the orderbook (partial fill)
-                    let order_to_reduce = orders.front_mut().unwrap();
-                    // remaining unfilled amount on last maker limit order
-                    let remainder = accumulator - market_sell_qty;
-                    // Only reduce FIFO Queue Orders
-                    println!("Order to reduce: {}", order_to_reduce);
-                    order_to_reduce.set_qty(remainder);
-                    // Set accumulator to final filled amount
-                    accumulator -= remainder;
-                    println!("Unfilled amount of last limit sell order: {}", remainder);
-                    break;
-                }
-                // Go to next element on same price level
-            } else {
-                // No orders left at the given price, go to a lower price level
-                let _ = self.remove_bid_price_level(&market_price).unwrap();
-                println!("Removed Price Level: {}", market_price);
+    /// Every resting bid in priority order, highest price first and FIFO within a price level
+    pub fn iter_bids(&self) -> impl Iterator<Item = RestingOrder> + '_ {
+        self.bids
+            .order_list
+            .iter()
+            .rev()
+            .flat_map(|level| RestingOrder::from_level(OrderType::Buy, level))
+    }
 
-                if let Some(next_matches) = self.highest_bids_mut() {
-                    price_level = next_matches;
+    /// Every resting ask in priority order, lowest price first and FIFO within a price level
+    pub fn iter_asks(&self) -> impl Iterator<Item = RestingOrder> + '_ {
+        self.asks
+            .order_list
+            .iter()
+            .flat_map(|level| RestingOrder::from_level(OrderType::Sell, level))
+    }
 
-                    market_price = price_level.0.clone();
+    /// Level-3 snapshot of every resting order of both sides
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(self.iter_bids().collect(), self.iter_asks().collect())
+    }
 
-                    orders = price_level.1;
-                } else {
-                    break;
-                }
-            };
-        }
+    fn highest_bid(&self) -> Option<(&Price, &IdentifiableOrder)> {
+        // get highest bid from buy side
+        let (price, orders) = self.bids.last()?;
+        Some((price, orders.front()?))
+    }
+
+    fn highest_bids_mut(&mut self) -> Option<(&Price, &mut OrderQueue)> {
+        // get highest bidders from buy side
+        self.bids.last_mut()
+    }
 
-        sell_order
-            .get_order_mut()
-            .set_qty(market_sell_qty - accumulator);
+    fn lowest_ask(&self) -> Option<(&Price, &IdentifiableOrder)> {
+        // get lowest ask price from sell side
+        let (price, orders) = self.asks.first()?;
+        Some((price, orders.front()?))
+    }
 
-        (true, market_sell_qty, accumulator, sell_order)
+    fn lowest_asks_mut(&mut self) -> Option<(&Price, &mut OrderQueue)> {
+        // get lowest ask price from sell side
+        self.asks.first_mut()
     }
 
-    /// Execute Market Buy Order.
+    /// Insert Limit Order on the side of the order
     ///
-    /// Behaves like an IOC Market Order, cancels any unfilled amount if orderbook lacks liquidity.
-    /// Removes Liquidity/Orders from the Orderbook.
-    fn market_buy(&mut self, buy_order: Order) -> (bool, u64, u64) {
-        // Market buy order quantity
-        let market_buy_qty = buy_order.get_order().get_qty();
-
-        // Get first sell orders to match
-        println!("{:?}", self.lowest_asks_mut());
-        let Some(mut price_level) = self.lowest_asks_mut() else {
-            // Orderbook is empty
-            return (false, market_buy_qty, 0);
+    /// Inserts the order without matching, it must not cross the spread.
+    pub fn insert_order(&mut self, mut insert_order: Order) -> Result<(), BookError> {
+        self.expire_due();
+        self.validate(&insert_order)?;
+        self.validate_price(&mut insert_order)?;
+        let crossed = match insert_order.get_order_type() {
+            OrderType::Buy => {
+                matches!(self.lowest_ask(), Some((lowest_ask, _)) if insert_order.get_price() >= lowest_ask)
+            }
+            OrderType::Sell => {
+                matches!(self.highest_bid(), Some((highest_bid, _)) if insert_order.get_price() <= highest_bid)
+            }
         };
-        // Market price level right now
-        let mut market_price = price_level.0.clone();
-        // Orders at price level
-        let mut orders = price_level.1;
-
-        println!("Market buy order quantity: {}", market_buy_qty);
-
-        // Accumulates the qty until it reaches the orders amount
-        let mut accumulator: u64 = 0;
-        while accumulator < market_buy_qty {
-            if let Some(matching_candidate) = orders.front() {
-                accumulator += matching_candidate.get_qty();
-                // Settle execution
-                if accumulator <= market_buy_qty {
-                    // We can remove matched order from the orderbook
-                    let order_to_remove = orders.pop_front().unwrap();
-                    // This is a rare case, this should be handled differently instead of branching unnecessarily in 99% of the cases
-                    if accumulator == market_buy_qty && orders.is_empty() {
-                        // No orders left at the given price
-                        // Remove price level from orderbook
-                        let _ = self.remove_ask_price_level(&market_price).unwrap();
-                        // We are done here
-                        break;
-                    }
-                    // Fire Event
-                    println!("Order to remove: {}", order_to_remove);
-                } else {
-                    // We need to reduce matched order in the orderbook (partial fill)
-                    let order_to_reduce = orders.front_mut().unwrap();
-                    // remaining unfilled amount on last maker limit order
-                    let remainder = accumulator - market_buy_qty;
-                    // Only reduce FIFO Queue Orders
-                    println!("Order to reduce: {}", order_to_reduce);
-                    order_to_reduce.set_qty(remainder);
-                    // Set accumulator to final filled amount
-                    accumulator -= remainder;
-                    // We can break since we are finished here
-                    println!("Unfilled amount of last limit sell order: {}", remainder);
-                    break;
-                }
-                // Go to next element on same price level
-            } else {
-                // No orders left at the given price, go to a higher price level
-                // 1. Remove price level from orderbook
-                let _ = self.remove_ask_price_level(&market_price).unwrap();
-                println!("Removed Price Level: {}", market_price);
-                // 2. Update to next price level
-                if let Some(next_matches) = self.lowest_asks_mut() {
-                    // Update Matches
-                    price_level = next_matches;
-                    // Update Market Price
-                    market_price = price_level.0.clone();
-                    // Update Orders
-                    orders = price_level.1;
-                } else {
-                    // Orderbook is empty
-                    break;
-                    // Unusual Outcome:
-                    // All orders are removed and the orderbook is empty
-                    // Market buy is only partially executed
-                }
-            };
+        if crossed {
+            return Err(BookError::CrossedBook);
         }
-
-        // Usual Outcome:
-        // All orders are removed including indexmap price levels if they are completely filled
-        // The last remaining order in the FIFO queue of the given price level was either exactly equal and was completely filled or only partially filled
-        (true, market_buy_qty, accumulator)
+        self.stamp(&mut insert_order);
+        self.rest_order(insert_order);
+        Ok(())
     }
 
-    /// Execute Market Sell Order.
+    /// Validates an incoming order before it is accepted
     ///
-    /// Behaves like an IOC Market Order, cancels any unfilled amount if orderbook lacks liquidity.
-    /// Removes Liquidity/Orders from the Orderbook.
-    fn market_sell(&mut self, sell_order: Order) -> (bool, u64, u64) {
-        // Market sell order quantity
-        let market_sell_qty = sell_order.get_order().get_qty();
-
-        // Get first buy orders to match
-        debug!("Highest Bids: {:?}", self.highest_bids_mut());
-        let Some(mut price_level) = self.highest_bids_mut() else {
-            // Orderbook is empty
-            return (false, market_sell_qty, 0);
+    /// Order ids have to be unique across both sides, this includes orders which are never inserted.
+    /// Otherwise fills and events could not be assigned to a single order.
+    /// The display quantity of an iceberg order has to follow the quantity rules as well.
+    fn validate(&self, order: &Order) -> Result<(), BookError> {
+        let qty = order.get_order().get_qty();
+        if qty == 0 {
+            return Err(BookError::ZeroQuantity);
+        }
+        let order_id = order.get_order().get_id();
+        if self.order_index.contains_key(&order_id) || self.stops.contains(order_id) {
+            return Err(BookError::DuplicateOrderId(order_id));
+        }
+        let expires_at = match order.get_time_in_force() {
+            TimeInForce::GoodTillDate(expires_at) => Some(expires_at),
+            TimeInForce::Day => Some(self.get_session_end()),
+            _ => None,
         };
-        debug!("Price level: {:?}", price_level);
-        // Market price level right now
-        let mut market_price = price_level.0.clone();
-        // Orders at price level
-        let mut orders = price_level.1;
-
-        debug!("Market sell order quantity: {}", market_sell_qty);
-
-        // Accumulates the qty until it reaches the orders amount
-        let mut accumulator: u64 = 0;
-        while accumulator < market_sell_qty {
-            if let Some(matching_candidate) = orders.front() {
-                accumulator += matching_candidate.get_qty();
-                // Settle execution
-                if accumulator <= market_sell_qty {
-                    // We can remove matched order from the orderbook
-                    let order_to_remove = orders.pop_front().unwrap();
-                    // This is a rare case, this should be handled differently instead of branching unnecessarily in 99% of the cases
-                    if accumulator == market_sell_qty && orders.is_empty() {
-                        // No orders left at the given price
-                        // Remove price level from orderbook
-                        let _ = self.remove_bid_price_level(&market_price).unwrap();
-                        // We are done here
-                        break;
-                    }
-                    // Fire Event
-                    debug!("Order to remove: {}", order_to_remove);
-                } else {
-                    // We need to reduce matched order in the orderbook (partial fill)
-                    let order_to_reduce = orders.front_mut().unwrap();
-                    // remaining unfilled amount on last maker limit order
-                    let remainder = accumulator - market_sell_qty;
-                    // Only reduce FIFO Queue Orders
-                    debug!("Order to reduce: {}", order_to_reduce);
-                    order_to_reduce.set_qty(remainder);
-                    // Set accumulator to final filled amount
-                    accumulator -= remainder;
-                    // We can break since we are finished here
-                    debug!("Unfilled amount of last limit buy order: {}", remainder);
-                    break;
-                }
-                // Go to next element on same price level
-            } else {
-                // No orders left at the given price, go to a lower price level
-                debug!("Orders in the FIFO Queue: {:?}", orders);
-                debug!("Current Price Level: {}", market_price);
-                // 1. Remove price level from orderbook
-                let _ = self.remove_bid_price_level(&market_price).unwrap();
-                debug!("Removed Price Level: {}", market_price);
-                // 2. Update to next price level
-                if let Some(next_matches) = self.highest_bids_mut() {
-                    // Update Matches
-                    price_level = next_matches;
-                    // Update Market Price
-                    market_price = price_level.0.clone();
-                    // Update Orders
-                    orders = price_level.1;
-                } else {
-                    // Orderbook is empty
-                    break;
-                    // Unusual Outcome:
-                    // All orders are removed and the orderbook is empty
-                    // Market sell is only partially executed
-                }
-            };
+        if expires_at.is_some_and(|expires_at| expires_at <= self.clock.now()) {
+            return Err(BookError::ExpiredOrder);
+        }
+        self.rules.validate_qty(qty)?;
+        match order.get_order().get_display_qty() {
+            Some(display_qty) if display_qty == 0 || display_qty > qty => {
+                Err(BookError::InvalidDisplayQuantity { display_qty, qty })
+            }
+            Some(display_qty) => self.rules.validate_qty(display_qty),
+            None => Ok(()),
         }
+    }
 
-        // Usual Outcome:
-        // All orders are removed including indexmap price levels if they are completely filled
-        // The last remaining order in the FIFO queue of the given price level was either exactly equal and was completely filled or only partially filled
-        (true, market_sell_qty, accumulator)
+    /// Validates the limit price of an incoming order against the rules of the instrument
+    ///
+    /// Converts the price to the scale of the instrument, off-tick prices are rejected.
+    /// The notional of a limit order is its price times its quantity.
+    fn validate_price(&self, order: &mut Order) -> Result<(), BookError> {
+        let price = self.rules.validate_price(order.get_price())?;
+        self.validate_limit_notional(&price, order.get_order().get_qty())?;
+        order.set_price(price);
+        Ok(())
     }
 
-    fn match_and_insert(&mut self, order: Order, order_type: OrderType) {
-        match order_type {
-            OrderType::Buy => {
-                //self.match_orders(self.sell_side.order_list, order.identifiable_order.get_qty());
-                let (result, qty, filled, order) = self.market_buy_until(order);
-                if order.get_order().get_qty() > 0 {
-                    self.insert_buy_order(order);
-                }
-            }
-            OrderType::Sell => {
-                let (result, qty, filled, order) = self.market_sell_until(order);
-                if order.get_order().get_qty() > 0 {
-                    self.insert_sell_order(order);
-                }
-            }
+    fn validate_limit_notional(&self, price: &Price, qty: u64) -> Result<(), BookError> {
+        if self.rules.get_max_notional().is_some() {
+            self.rules.validate_notional(price.checked_mul_qty(qty)?)?;
         }
+        Ok(())
     }
 
-    fn limit_or_cancel_insert(&mut self, order: Order, order_type: OrderType) {
-        match order_type {
-            OrderType::Buy => {}
-            OrderType::Sell => {}
+    /// Validates the notional of an incoming market order against the rules of the instrument
+    ///
+    /// Market orders have no limit price, their notional is the value of the fills they would receive.
+    fn validate_market_notional(&self, order: &Order) -> Result<(), BookError> {
+        if self.rules.get_max_notional().is_some() {
+            let qty = order.get_order().get_qty();
+            let notional = match order.get_order_type() {
+                OrderType::Buy => self.asks.ask_notional_for(qty, |id| self.other_leg(id))?,
+                OrderType::Sell => self.bids.bid_notional_for(qty, |id| self.other_leg(id))?,
+            };
+            self.rules.validate_notional(notional)?;
         }
+        Ok(())
     }
 
-    fn immediate_or_cancel_insert(&mut self, order: Order, order_type: OrderType) {
-        match order_type {
-            OrderType::Buy => {}
-            OrderType::Sell => {}
+    /// Inserts the order at the back of its price level, iceberg orders only show their display quantity
+    fn rest_order(&mut self, mut insert_order: Order) {
+        if let Some(expires_at) = insert_order.get_order().get_expires_at() {
+            self.expiries
+                .insert((expires_at, insert_order.get_order().get_id()));
+        }
+        let order_type = insert_order.get_order_type();
+        let order_list = match order_type {
+            OrderType::Buy => &mut self.bids,
+            OrderType::Sell => &mut self.asks,
+        };
+        let price = insert_order.get_price().clone();
+        let order_id = insert_order.get_order().get_id();
+        insert_order.get_order_mut().hide_reserve();
+        let rested = BookEvent::OrderRested {
+            order_id,
+            order_type,
+            price: price.clone(),
+            qty: insert_order.get_order().get_qty(),
+            hidden_qty: insert_order.get_order().get_hidden_qty(),
+        };
+        // Insert Limit Order
+        let new_price_level = order_list.insert_order(insert_order);
+        // Order was pushed to the back of the FIFO queue
+        if let Some(handle) = order_list
+            .order_list
+            .get(&price)
+            .and_then(OrderQueue::back_handle)
+        {
+            self.order_index.insert(
+                order_id,
+                OrderLocation::new(order_type, price.clone(), handle),
+            );
+        }
+
+        if new_price_level {
+            self.emit(BookEvent::PriceLevelAdded {
+                order_type,
+                price: price.clone(),
+            });
         }
+        self.emit(rested);
+        self.emit_level(order_type, &price);
     }
 
-    fn fill_or_kill_insert(&mut self, order: Order, order_type: OrderType) {
-        match order_type {
-            OrderType::Buy => {}
-            OrderType::Sell => {}
+    /// Removes the given resting order from its side of the orderbook
+    ///
+    /// Removes the links of the order like [OrderBook::cancel].
+    pub fn remove_order(&mut self, remove_order: Order) -> Result<(), BookError> {
+        let order_id = remove_order.get_order().get_id();
+        // Orders are matched by their side, price, id and visible quantity
+        let is_resting = self.order_index.get(&order_id).is_some_and(|location| {
+            location.order_type == remove_order.get_order_type()
+                && &location.price == remove_order.get_price()
+        }) && self
+            .resting_order(order_id)
+            .is_some_and(|order| order.get_qty() == remove_order.get_order().get_qty());
+        if is_resting {
+            let (location, removed, level_removed) = self.take_order(order_id)?;
+            self.unlink(order_id);
+            self.emit_removed(
+                location.order_type,
+                &location.price,
+                &removed,
+                level_removed,
+            );
+            return Ok(());
+        }
+
+        // Orders missing from the index, e.g. sharing their id with an order on the other side, are searched on their price level
+        let order_type = remove_order.get_order_type();
+        let order_book = match order_type {
+            OrderType::Buy => &mut self.bids.order_list,
+            OrderType::Sell => &mut self.asks.order_list,
+        };
+        let price = remove_order.get_price().clone();
+        let removed = remove_order.get_order().clone();
+        if !Self::remove_from_orders(remove_order, order_book) {
+            return Err(BookError::UnknownOrder(order_id));
         }
+        let level_removed = !order_book.contains_key(&price);
+        self.emit_removed(order_type, &price, &removed, level_removed);
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+    fn emit_removed(
+        &mut self,
+        order_type: OrderType,
+        price: &Price,
+        removed: &IdentifiableOrder,
+        level_removed: bool,
+    ) {
+        self.emit(BookEvent::OrderCancelled {
+            order_id: removed.get_id(),
+            order_type,
+            qty: removed.get_qty(),
+            hidden_qty: removed.get_hidden_qty(),
+        });
+        if level_removed {
+            self.emit(BookEvent::PriceLevelRemoved {
+                order_type,
+                price: price.clone(),
+            });
+        }
+        self.emit_level(order_type, price);
+    }
 
-    use proptest::prelude::*;
-    use rand::Rng;
-    use tracing::{event, Level};
-    use tracing_subscriber::{EnvFilter, FmtSubscriber};
+    /// Order Modification: Cancel an Order by its id
+    ///
+    /// Removes the resting order with its remaining quantity from the orderbook.
+    /// Stop orders which have not been triggered yet are cancelled as well.
+    /// Cancelling one order of an OCO pair leaves the other order without a link, cancelling a bracket entry discards its exit orders.
+    pub fn cancel(&mut self, order_id: u64) -> Result<CancelledOrder, BookError> {
+        if let Some(stop_order) = self.stops.remove(order_id) {
+            self.unlink(order_id);
+            let (qty, hidden_qty) = stop_order.get_order().split_reserve();
+            self.emit(BookEvent::OrderCancelled {
+                order_id,
+                order_type: stop_order.get_order_type(),
+                qty,
+                hidden_qty,
+            });
+            return Ok(CancelledOrder::new(stop_order));
+        }
+        let (location, removed, level_removed) = self.take_order(order_id)?;
+        self.unlink(order_id);
+        self.emit_removed(
+            location.order_type,
+            &location.price,
+            &removed,
+            level_removed,
+        );
+        Ok(CancelledOrder::new(Order::new(
+            location.order_type,
+            location.price,
+            removed,
+        )))
+    }
 
-    // Debug Tracing for tests
-    fn initialize_tracing() {
-        // Create a `LevelFilter` with the desired tracing level
-        let filter = tracing::Level::DEBUG;
+    /// Order Modification: Amend quantity and/or price of an Order by its id
+    ///
+    /// Reducing only the quantity keeps the time priority of the order.
+    /// Changing the price or increasing the quantity moves the order to the back of the FIFO queue of the new price level,
+    /// it is matched against the opposite side first if it crosses the spread at the new price.
+    /// Amending the quantity to zero cancels the order.
+    pub fn amend(
+        &mut self,
+        order_id: u64,
+        new_qty: u64,
+        new_price: Price,
+    ) -> Result<ExecutionReport, BookError> {
+        self.expire_due();
+        let new_price = self.rules.validate_price(&new_price)?;
+        if new_qty > 0 {
+            self.rules.validate_qty(new_qty)?;
+            self.validate_limit_notional(&new_price, new_qty)?;
+        }
+        let location = self
+            .order_index
+            .get(&order_id)
+            .ok_or(BookError::UnknownOrder(order_id))?;
+        let order_type = location.order_type;
 
-        // Create a `FmtSubscriber` with the filter and desired formatting options
-        let subscriber = tracing_subscriber::fmt().with_max_level(filter).finish();
+        if new_qty == 0 {
+            let cancelled = self.cancel(order_id)?;
+            let mut report = ExecutionReport::new(cancelled.get_order());
+            report.cancel(cancelled.get_order().get_order().get_total_qty());
+            return Ok(report);
+        }
 
-        // Set the subscriber as the global default
-        tracing::subscriber::set_global_default(subscriber)
-            .expect("Failed to set tracing subscriber");
+        if new_price == location.price {
+            let order_list = match order_type {
+                OrderType::Buy => &mut self.bids.order_list,
+                OrderType::Sell => &mut self.asks.order_list,
+            };
+            let reduced = order_list
+                .get_mut(&location.price)
+                .and_then(|orders| {
+                    orders.update(location.handle, |resting_order| {
+                        if new_qty > resting_order.get_total_qty() {
+                            return None;
+                        }
+                        // Reduce in place, the order keeps its position in the FIFO queue
+                        resting_order.reduce_total_qty(new_qty);
+                        Some(resting_order.clone())
+                    })
+                })
+                .ok_or(BookError::UnknownOrder(order_id))?;
+
+            if let Some(resting_order) = reduced {
+                let amended = Order::new(order_type, new_price.clone(), resting_order);
+                let mut report = ExecutionReport::new(&amended);
+                let qty = amended.get_order().get_qty();
+                let hidden_qty = amended.get_order().get_hidden_qty();
+                report.set_resting(amended);
+                self.emit(BookEvent::OrderAmended {
+                    order_id,
+                    order_type,
+                    price: new_price.clone(),
+                    qty,
+                    hidden_qty,
+                });
+                self.emit_level(order_type, &new_price);
+                return Ok(report);
+            }
+        }
+
+        // Order loses its time priority and is handled like a new limit order
+        let (location, removed, level_removed) = self.take_order(order_id)?;
+        if level_removed {
+            self.emit(BookEvent::PriceLevelRemoved {
+                order_type,
+                price: location.price.clone(),
+            });
+        }
+        let mut amended_order = IdentifiableOrder::new(order_id, new_qty);
+        if let Some(display_qty) = removed.get_display_qty() {
+            amended_order = amended_order.with_display_qty(display_qty);
+        }
+        let (qty, hidden_qty) = amended_order.split_reserve();
+        self.emit(BookEvent::OrderAmended {
+            order_id,
+            order_type,
+            price: new_price.clone(),
+            qty,
+            hidden_qty,
+        });
+        self.emit_level(order_type, &location.price);
+        amended_order.set_expires_at(removed.get_expires_at());
+        let mut amended = Order::new(order_type, new_price, amended_order);
+        self.stamp(&mut amended);
+        let mut report = ExecutionReport::new(&amended);
+        self.match_and_rest(amended, &mut report);
+        Ok(report)
     }
 
-    proptest! {
-       #[test]
-       fn test_order_book(qty: u32, main_unit: u32, sub_unit: u8) {
-            let order_book = fill_bids_pseudorandom();
-       }
+    /// Cancels all resting and stop orders which expire at or before `now`, in order of their expiry time.
+    ///
+    /// Fires the same events as [OrderBook::cancel] for every expired order.
+    /// Orders which have expired according to the clock of the orderbook are cancelled before any incoming order is processed as well,
+    /// so they are never executed.
+    pub fn expire_until(&mut self, now: u64) -> Vec<CancelledOrder> {
+        let mut expired = vec![];
+        while let Some(&(expires_at, order_id)) = self.expiries.first() {
+            if expires_at > now {
+                break;
+            }
+            self.expiries.pop_first();
+            // Order may have been filled or cancelled already
+            if self.get_expires_at(order_id) != Some(expires_at) {
+                continue;
+            }
+            if let Ok(cancelled) = self.cancel(order_id) {
+                expired.push(cancelled);
+            }
+        }
+        expired
     }
 
-    // Pseudorandom Fill
-    fn fill_bids_pseudorandom() -> (OrderList, Vec<Order>) {
-        let mut bid_list = OrderList::default();
-        let mut remove_list = vec![];
-        let mut rng = rand::thread_rng();
+    /// Cancels all orders which have expired according to the clock of the orderbook
+    fn expire_due(&mut self) {
+        let now = self.clock.now();
+        self.expire_until(now);
+    }
 
-        for _ in 0..1000 {
-            let price = rng.gen_range(1..=100) as f64;
-            let mut hasher = DefaultHasher::new();
-            hasher.write_i64(price as i64);
-            let qty = hasher.finish() % 250_000;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            remove_list.push(order.clone());
-            bid_list.insert_order(order);
+    /// Expiry time of an open resting or stop order
+    fn get_expires_at(&self, order_id: u64) -> Option<u64> {
+        if let Some(stop_order) = self.stops.get(order_id) {
+            return stop_order.get_order().get_expires_at();
         }
+        self.resting_order(order_id)?.get_expires_at()
+    }
 
-        (bid_list, remove_list)
+    /// Resting order by its id, looked up through the order index
+    fn resting_order(&self, order_id: u64) -> Option<&IdentifiableOrder> {
+        let location = self.order_index.get(&order_id)?;
+        let order_list = match location.order_type {
+            OrderType::Buy => &self.bids.order_list,
+            OrderType::Sell => &self.asks.order_list,
+        };
+        order_list.get(&location.price)?.get(location.handle)
     }
 
-    // Pseudorandom Fill
-    fn fill_asks_pseudorandom() -> (OrderList, Vec<Order>) {
-        let mut ask_list = OrderList::default();
-        let mut remove_list = vec![];
-        let mut rng = rand::thread_rng();
+    /// Removes a resting order from the orderbook and the order index without firing any events
+    ///
+    /// Returns its location, the removed order and whether its price level has been removed.
+    fn take_order(
+        &mut self,
+        order_id: u64,
+    ) -> Result<(OrderLocation, IdentifiableOrder, bool), BookError> {
+        let location = self
+            .order_index
+            .remove(&order_id)
+            .ok_or(BookError::UnknownOrder(order_id))?;
+        let order_list = match location.order_type {
+            OrderType::Buy => &mut self.bids.order_list,
+            OrderType::Sell => &mut self.asks.order_list,
+        };
 
-        for _ in 0..1000 {
-            let price = rng.gen_range(1..=100) as f64;
-            let mut hasher = DefaultHasher::new();
-            hasher.write_i64(price as i64);
-            let qty = hasher.finish() % 250_000;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            remove_list.push(order.clone());
-            ask_list.insert_order(order);
+        let orders_on_price_level = order_list
+            .get_mut(&location.price)
+            .ok_or(BookError::UnknownOrder(order_id))?;
+        let removed = orders_on_price_level
+            .remove(location.handle)
+            .ok_or(BookError::UnknownOrder(order_id))?;
+        let level_removed = orders_on_price_level.is_empty();
+        if level_removed {
+            order_list.remove(&location.price); // O(log n)
         }
+        Ok((location, removed, level_removed))
+    }
 
-        (ask_list, remove_list)
+    /// Cancels all orders of the given ask price level, see [OrderBook::remove_bid_price_level]
+    pub fn remove_ask_price_level(&mut self, key: &Price) -> Option<VecDeque<IdentifiableOrder>> {
+        self.remove_price_level(OrderType::Sell, key)
     }
 
-    // Pseudorandom Orders
-    fn generate_orders_pseudorandom(amount: u64) -> Vec<Order> {
-        let mut orders = vec![];
-        let mut rng = rand::thread_rng();
+    /// Cancels all orders of the given bid price level.
+    ///
+    /// Every order is cancelled like by [OrderBook::cancel] in the order of the FIFO queue,
+    /// followed by the removal of the price level.
+    pub fn remove_bid_price_level(&mut self, key: &Price) -> Option<VecDeque<IdentifiableOrder>> {
+        self.remove_price_level(OrderType::Buy, key)
+    }
 
-        for _ in 0..amount {
-            let price = rng.gen_range(1..=100) as f64;
-            let mut hasher = DefaultHasher::new();
-            hasher.write_i64(price as i64);
-            let qty = hasher.finish() % 250_000;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            orders.push(order);
+    fn remove_price_level(
+        &mut self,
+        order_type: OrderType,
+        key: &Price,
+    ) -> Option<VecDeque<IdentifiableOrder>> {
+        let removed = self.take_price_level(order_type, key)?;
+        for order in removed.iter() {
+            self.unlink(order.get_id());
+            if let Some(expires_at) = order.get_expires_at() {
+                self.expiries.remove(&(expires_at, order.get_id()));
+            }
+            self.emit(BookEvent::OrderCancelled {
+                order_id: order.get_id(),
+                order_type,
+                qty: order.get_qty(),
+                hidden_qty: order.get_hidden_qty(),
+            });
         }
-
-        orders
+        self.emit(BookEvent::PriceLevelRemoved {
+            order_type,
+            price: key.clone(),
+        });
+        self.emit_level(order_type, key);
+        Some(removed.into_iter().collect())
     }
 
-    use super::*;
-    #[test]
-    fn test_inserts() {
-        let (buy_side, _) = fill_bids_pseudorandom();
-        assert_eq!(buy_side.order_list.len(), 100);
-        let (sell_side, _) = fill_asks_pseudorandom();
-        assert_eq!(sell_side.order_list.len(), 100);
+    /// Removes a price level from the orderbook and the order index without firing any events
+    fn take_price_level(&mut self, order_type: OrderType, key: &Price) -> Option<OrderQueue> {
+        let removed = match order_type {
+            OrderType::Buy => self.bids.remove_price_level(key),
+            OrderType::Sell => self.asks.remove_price_level(key),
+        };
+        self.remove_from_index(removed.as_ref());
+        removed
     }
 
+    fn remove_from_index(&mut self, removed: Option<&OrderQueue>) {
+        for order in removed.into_iter().flat_map(OrderQueue::iter) {
+            self.order_index.remove(&order.get_id());
+        }
+    }
+
+    /// Order Modification: Remove/Cancel an Order
+    ///
+    /// Orders are matched by their id and visible quantity, returns true if the order has been found and removed.
+    pub fn remove_from_orders(remove_order: Order, order_book: &mut Orders) -> bool {
+        let Some(orders_on_price_level) = order_book.get_mut(remove_order.get_price()) else {
+            return false;
+        };
+        let Some(handle) = orders_on_price_level.find(|order| {
+            order.get_id() == remove_order.get_order().get_id()
+                && order.get_qty() == remove_order.get_order().get_qty()
+        }) else {
+            return false;
+        };
+        // Only delete the relevant entry
+        orders_on_price_level.remove(handle);
+        if orders_on_price_level.is_empty() {
+            // If there was only one entry, we can delete the whole price level
+            order_book.remove(remove_order.get_price()); // O(log n)
+        }
+        true
+    }
+}
+
+impl<L: EventListener> OrderBook<L> {
+    fn best_opposite_mut(&mut self, order_type: OrderType) -> Option<(&Price, &mut OrderQueue)> {
+        match order_type {
+            OrderType::Buy => self.lowest_asks_mut(),
+            OrderType::Sell => self.highest_bids_mut(),
+        }
+    }
+
+    /// Matches the taker order against the opposite side in price/time priority.
+    ///
+    /// Stops as soon as the taker order is filled, the opposite side is empty or,
+    /// if `until_limit` is set, the best opposite price is worse than the taker's limit price.
+    /// The taker order is reduced by the filled amount and every fill is added to the report.
+    fn match_order(
+        &mut self,
+        taker_order: &mut Order,
+        until_limit: bool,
+        report: &mut ExecutionReport,
+    ) {
+        let taker_id = taker_order.get_order().get_id();
+        let order_type = taker_order.get_order_type();
+        // All fills of one taker order happen at the same time
+        let timestamp = self.clock.now();
+        // Opposite price level which has been reduced but not removed
+        let mut reduced_level = None;
+
+        while taker_order.get_order().get_qty() > 0 {
+            let Some((price_level, orders)) = self.best_opposite_mut(order_type) else {
+                // Unusual Outcome:
+                // All orders are removed and the orderbook side is empty
+                // Taker order is only partially executed
+                break;
+            };
+
+            // Check until
+            let crosses = match order_type {
+                OrderType::Buy => taker_order.get_price() >= price_level,
+                OrderType::Sell => taker_order.get_price() <= price_level,
+            };
+            if until_limit && !crosses {
+                // Best opposite price is beyond the limit price, nothing left to match
+                break;
+            }
+            // Market price level right now
+            let market_price = price_level.clone();
+
+            // Settle execution
+            let taker_qty = taker_order.get_order().get_qty();
+            let Some((maker_id, fill_qty, maker_remaining, replenished, maker_open, visible_qty)) =
+                orders.update_front(|maker_order| {
+                    let fill_qty = maker_order.get_qty().min(taker_qty);
+                    let maker_remaining = maker_order.get_qty() - fill_qty;
+                    maker_order.set_qty(maker_remaining);
+                    let replenished = maker_order.replenish();
+                    (
+                        maker_order.get_id(),
+                        fill_qty,
+                        maker_remaining,
+                        replenished,
+                        // Open quantity of the maker order including its hidden reserve
+                        maker_order.get_total_qty(),
+                        maker_order.get_qty(),
+                    )
+                })
+            else {
+                // Empty price levels are always removed, this should never be reached
+                let _ = self.take_price_level(order_type.opposite(), &market_price);
+                continue;
+            };
+            let taker_remaining = taker_qty - fill_qty;
+            taker_order.get_order_mut().set_qty(taker_remaining);
+
+            if replenished {
+                // Iceberg order shows its next peak and loses its time priority, its handle stays valid
+                orders.requeue_front();
+            } else if maker_remaining == 0 {
+                // We can remove the matched order from the orderbook
+                let order_to_remove = orders.pop_front();
+                debug!("Order to remove: {:?}", order_to_remove);
+            } else {
+                // Maker order was only partially filled, it keeps its position in the FIFO queue
+                debug!("Order to reduce: {}", maker_id);
+            }
+            let level_empty = orders.is_empty();
+
+            if maker_open == 0 {
+                self.order_index.remove(&maker_id);
+            }
+            self.last_traded_price = Some(market_price.clone());
+            self.record_trade(Trade::new(
+                self.last_trade_id + 1,
+                market_price.clone(),
+                fill_qty,
+                order_type,
+                maker_id,
+                taker_id,
+                timestamp,
+            ));
+            report.add_fill(Fill::new(
+                maker_id,
+                taker_id,
+                market_price.clone(),
+                fill_qty,
+            ));
+            // Public fill events only show the peak, the next one follows with the replenish event
+            self.emit_fill(
+                maker_id,
+                order_type.opposite(),
+                taker_id,
+                &market_price,
+                fill_qty,
+                (maker_remaining, maker_open - maker_remaining),
+            );
+            self.emit_fill(
+                taker_id,
+                order_type,
+                maker_id,
+                &market_price,
+                fill_qty,
+                (taker_remaining, 0),
+            );
+            if replenished {
+                self.emit(BookEvent::OrderReplenished {
+                    order_id: maker_id,
+                    order_type: order_type.opposite(),
+                    price: market_price.clone(),
+                    qty: visible_qty,
+                });
+            }
+            self.on_fill(maker_id, maker_open == 0);
+            self.on_fill(taker_id, taker_remaining == 0);
+            let opposite_list = match order_type {
+                OrderType::Buy => &self.asks.order_list,
+                OrderType::Sell => &self.bids.order_list,
+            };
+            // Cancelling the other order of an OCO pair may have removed the level already
+            let level_removed = !opposite_list.contains_key(&market_price);
+
+            if level_removed {
+                reduced_level = None;
+            } else if level_empty {
+                // No orders left at the given price, go to the next price level
+                let _ = self.take_price_level(order_type.opposite(), &market_price);
+                debug!("Removed Price Level: {}", market_price);
+                self.emit(BookEvent::PriceLevelRemoved {
+                    order_type: order_type.opposite(),
+                    price: market_price.clone(),
+                });
+                self.emit_level(order_type.opposite(), &market_price);
+                reduced_level = None;
+            } else {
+                reduced_level = Some(market_price);
+            }
+        }
+        if let Some(price) = reduced_level {
+            self.emit_level(order_type.opposite(), &price);
+        }
+        // Usual Outcome:
+        // All matched orders are removed including price levels if they are completely filled
+        // The last matched maker order was either exactly equal and was completely filled or only partially filled
+    }
+
+    fn record_trade(&mut self, trade: Trade) {
+        self.last_trade_id = trade.get_trade_id();
+        self.session_stats.record(&trade);
+        for builder in self.candle_builders.iter_mut() {
+            builder.record(&trade);
+        }
+        self.trade_tape.push(trade);
+    }
+
+    fn emit_fill(
+        &mut self,
+        order_id: u64,
+        order_type: OrderType,
+        counterparty_id: u64,
+        price: &Price,
+        qty: u64,
+        (remaining_qty, hidden_qty): (u64, u64),
+    ) {
+        let event = if remaining_qty == 0 && hidden_qty == 0 {
+            BookEvent::OrderFilled {
+                order_id,
+                order_type,
+                counterparty_id,
+                price: price.clone(),
+                qty,
+            }
+        } else {
+            BookEvent::OrderPartiallyFilled {
+                order_id,
+                order_type,
+                counterparty_id,
+                price: price.clone(),
+                qty,
+                remaining_qty,
+                hidden_qty,
+            }
+        };
+        self.emit(event);
+    }
+
+    /// Creates the report for an incoming order
+    fn accept(&mut self, order: &mut Order) -> ExecutionReport {
+        self.stamp(order);
+        let (qty, hidden_qty) = order.get_order().split_reserve();
+        self.emit(BookEvent::OrderAccepted {
+            order_id: order.get_order().get_id(),
+            order_type: order.get_order_type(),
+            price: order.get_price().clone(),
+            qty,
+            hidden_qty,
+        });
+        ExecutionReport::new(order)
+    }
+
+    /// Cancels the open quantity of an order which never entered the orderbook
+    fn cancel_remainder(&mut self, order: &Order, report: &mut ExecutionReport) {
+        let qty = order.get_order().get_qty();
+        if qty > 0 {
+            self.unlink(order.get_order().get_id());
+            report.cancel(qty);
+            let (qty, hidden_qty) = order.get_order().split_reserve();
+            self.emit(BookEvent::OrderCancelled {
+                order_id: order.get_order().get_id(),
+                order_type: order.get_order_type(),
+                qty,
+                hidden_qty,
+            });
+        }
+    }
+
+    /// Matches the order and inserts any unfilled remainder into the orderbook.
+    fn match_and_rest(&mut self, mut order: Order, report: &mut ExecutionReport) {
+        self.match_order(&mut order, true, report);
+        if order.get_order().get_qty() > 0 {
+            report.set_resting(order.clone());
+            self.rest_order(order);
+        }
+        self.process_triggers();
+    }
+
+    /// Matches the order and cancels any unfilled remainder.
+    fn match_and_cancel(
+        &mut self,
+        mut order: Order,
+        until_limit: bool,
+    ) -> Result<ExecutionReport, BookError> {
+        self.expire_due();
+        self.validate(&order)?;
+        // Market orders are not limited by their price
+        if until_limit {
+            self.validate_price(&mut order)?;
+        } else {
+            self.validate_market_notional(&order)?;
+        }
+        let mut report = self.accept(&mut order);
+        self.match_order(&mut order, until_limit, &mut report);
+        self.cancel_remainder(&order, &mut report);
+        self.process_triggers();
+        Ok(report)
+    }
+
+    fn stop_triggered(&self, order_type: OrderType, stop_price: &Price) -> bool {
+        match (&self.last_traded_price, order_type) {
+            (Some(last_traded_price), OrderType::Buy) => last_traded_price >= stop_price,
+            (Some(last_traded_price), OrderType::Sell) => last_traded_price <= stop_price,
+            (None, _) => false,
+        }
+    }
+
+    /// Submits the exit orders of filled bracket entries and executes all stop orders triggered by the last traded price.
+    ///
+    /// Each triggered order is executed completely before the next stop order is checked against the new last traded price,
+    /// so cascading stops are executed within the same call in a deterministic order.
+    /// A triggered order which is rejected, e.g. for exceeding the maximum notional, is cancelled.
+    fn process_triggers(&mut self) {
+        if self.triggering {
+            // Outer loop picks up everything triggered by this execution
+            return;
+        }
+        self.triggering = true;
+        loop {
+            if let Some((take_profit, stop_loss)) = self.activated_brackets.pop_front() {
+                self.activate_bracket(take_profit, stop_loss);
+                continue;
+            }
+            let Some(stop_order) = self
+                .last_traded_price
+                .clone()
+                .and_then(|last_traded_price| self.stops.pop_triggered(&last_traded_price))
+            else {
+                break;
+            };
+            self.execute_triggered(stop_order);
+        }
+        self.triggering = false;
+    }
+
+    fn execute_triggered(&mut self, stop_order: Order) {
+        let order_id = stop_order.get_order().get_id();
+        let order_type = stop_order.get_order_type();
+        let (qty, hidden_qty) = stop_order.get_order().split_reserve();
+        if let Some(stop_price) = stop_order.get_stop_price() {
+            self.emit(BookEvent::StopTriggered {
+                order_id,
+                order_type,
+                stop_price: stop_price.clone(),
+            });
+        }
+        if let Err(error) = self.submit(stop_order.triggered()) {
+            debug!("Triggered stop order {} rejected: {}", order_id, error);
+            self.unlink(order_id);
+            self.emit(BookEvent::OrderCancelled {
+                order_id,
+                order_type,
+                qty,
+                hidden_qty,
+            });
+        }
+    }
+
+    /// Validates an order of a group without submitting it
+    ///
+    /// Market orders and stop market orders which would be triggered right away are checked against the current orderbook.
+    fn validate_group_order(&self, order: &Order) -> Result<(), BookError> {
+        self.validate(order)?;
+        if matches!(order.get_kind(), OrderKind::Limit | OrderKind::StopLimit) {
+            self.validate_price(&mut order.clone())?;
+        }
+        if order.get_kind() == OrderKind::Market {
+            self.validate_market_notional(order)?;
+        }
+        if matches!(
+            order.get_kind(),
+            OrderKind::StopMarket | OrderKind::StopLimit
+        ) {
+            let stop_price = order.get_stop_price().ok_or(BookError::InvalidStopOrder)?;
+            let stop_price = self.rules.validate_price(stop_price)?;
+            if order.get_kind() == OrderKind::StopMarket
+                && self.stop_triggered(order.get_order_type(), &stop_price)
+            {
+                self.validate_market_notional(order)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports an order of a group which is cancelled without being submitted
+    fn cancel_unsubmitted(&mut self, mut order: Order) -> ExecutionReport {
+        let mut report = self.accept(&mut order);
+        self.cancel_remainder(&order, &mut report);
+        report
+    }
+
+    /// Applies the links of an order after it has been executed.
+    ///
+    /// Cancels the other order of an OCO pair right away, so that it can not be executed by the same taker order.
+    /// Exit orders of a filled bracket entry are submitted after the current match.
+    fn on_fill(&mut self, order_id: u64, filled: bool) {
+        match self.links.get(&order_id) {
+            Some(&OrderLink::Oco { other_id, trigger })
+                if filled || trigger == OcoTrigger::PartialFill =>
+            {
+                self.links.remove(&order_id);
+                self.links.insert(other_id, OrderLink::OcoCancelled);
+                if self.cancel(other_id).is_err() {
+                    debug!("OCO order {} has not been submitted yet", other_id);
+                }
+            }
+            Some(OrderLink::BracketEntry { .. }) if filled => {
+                if let Some(OrderLink::BracketEntry {
+                    take_profit,
+                    stop_loss,
+                }) = self.links.remove(&order_id)
+                {
+                    self.activated_brackets
+                        .push_back((*take_profit, *stop_loss));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Sets the time the order is added to the orderbook and its expiry time
+    ///
+    /// Orders without an expiring time in force keep their expiry time, e.g. after an amendment.
+    fn stamp(&self, order: &mut Order) {
+        let expires_at = match order.get_time_in_force() {
+            TimeInForce::GoodTillDate(expires_at) => Some(expires_at),
+            TimeInForce::Day => Some(self.get_session_end()),
+            _ => order.get_order().get_expires_at(),
+        };
+        let identifiable_order = order.get_order_mut();
+        identifiable_order.set_timestamp(self.clock.now());
+        identifiable_order.set_expires_at(expires_at);
+    }
+
+    /// Other order of an OCO pair, which is cancelled once the given order is executed
+    fn other_leg(&self, order_id: u64) -> Option<u64> {
+        match self.links.get(&order_id) {
+            Some(&OrderLink::Oco { other_id, .. }) => Some(other_id),
+            _ => None,
+        }
+    }
+
+    /// Removes the links of an order which is no longer open
+    fn unlink(&mut self, order_id: u64) {
+        if let Some(OrderLink::Oco { other_id, .. }) = self.links.remove(&order_id) {
+            self.links.remove(&other_id);
+        }
+    }
+
+    fn activate_bracket(&mut self, take_profit: Order, stop_loss: Order) {
+        if let Err(error) =
+            self.one_cancels_other_insert(take_profit.clone(), stop_loss.clone(), OcoTrigger::Fill)
+        {
+            debug!("Bracket exit orders rejected: {}", error);
+            for order in [take_profit, stop_loss] {
+                let (qty, hidden_qty) = order.get_order().split_reserve();
+                self.emit(BookEvent::OrderCancelled {
+                    order_id: order.get_order().get_id(),
+                    order_type: order.get_order_type(),
+                    qty,
+                    hidden_qty,
+                });
+            }
+        }
+    }
+}
+
+impl<L: EventListener> MatchingEngine for OrderBook<L> {
+    /// Routes the order by its kind and time in force.
+    ///
+    /// The outcome of a post only order is reflected in the report,
+    /// a rejected order is cancelled and a repriced order is resting at its new price.
+    fn submit(&mut self, order: Order) -> Result<ExecutionReport, BookError> {
+        match (order.get_kind(), order.get_time_in_force()) {
+            (OrderKind::Market, _) => self.market(order),
+            (OrderKind::StopMarket | OrderKind::StopLimit, _) => self.stop_insert(order),
+            (
+                OrderKind::Limit,
+                TimeInForce::GoodTillCancel | TimeInForce::GoodTillDate(_) | TimeInForce::Day,
+            ) => self.match_and_insert(order),
+            (OrderKind::Limit, TimeInForce::ImmediateOrCancel) => {
+                self.immediate_or_cancel_insert(order)
+            }
+            (OrderKind::Limit, TimeInForce::FillOrKill) => self.fill_or_kill_insert(order),
+            (OrderKind::Limit, TimeInForce::PostOnly(mode)) => self
+                .limit_or_cancel_insert(order, mode)
+                .map(|(_, report)| report),
+        }
+    }
+
+    /// Execute Market Order.
+    ///
+    /// Behaves like an IOC Market Order, cancels any unfilled amount if orderbook lacks liquidity.
+    /// Removes Liquidity/Orders from the Orderbook.
+    fn market(&mut self, order: Order) -> Result<ExecutionReport, BookError> {
+        self.match_and_cancel(order, false)
+    }
+
+    /// Execute Limit Order against the orderbook.
+    ///
+    /// Removes Liquidity/Orders from the Orderbook until the price of the order is reached.
+    /// The unfilled amount is cancelled.
+    fn market_until(&mut self, order: Order) -> Result<ExecutionReport, BookError> {
+        self.match_and_cancel(order, true)
+    }
+
+    fn match_and_insert(&mut self, mut order: Order) -> Result<ExecutionReport, BookError> {
+        self.expire_due();
+        self.validate(&order)?;
+        self.validate_price(&mut order)?;
+        let mut report = self.accept(&mut order);
+        self.match_and_rest(order, &mut report);
+        Ok(report)
+    }
+
+    /// Insert Post Only Limit Order.
+    ///
+    /// The order is only inserted if no part of it would be executed against the opposite side.
+    /// If it would cross the spread, it is either rejected or repriced one tick of the instrument behind the best opposite price.
+    /// A repriced order is rejected if it exceeds the maximum notional at its new price.
+    fn limit_or_cancel_insert(
+        &mut self,
+        mut order: Order,
+        mode: PostOnlyMode,
+    ) -> Result<(PostOnlyOutcome, ExecutionReport), BookError> {
+        self.expire_due();
+        self.validate(&order)?;
+        self.validate_price(&mut order)?;
+        let mut report = self.accept(&mut order);
+
+        // Price one tick behind the best opposite price, if the order would cross the spread
+        let behind_best = match order.get_order_type() {
+            OrderType::Buy => match self.lowest_ask() {
+                Some((lowest_ask, _)) if order.get_price() >= lowest_ask => {
+                    Some(self.rules.prev_price(lowest_ask))
+                }
+                _ => None,
+            },
+            OrderType::Sell => match self.highest_bid() {
+                Some((highest_bid, _)) if order.get_price() <= highest_bid => {
+                    Some(self.rules.next_price(highest_bid))
+                }
+                _ => None,
+            },
+        };
+
+        let outcome = match (behind_best, mode) {
+            (None, _) => PostOnlyOutcome::Accepted,
+            // No valid price left behind the best opposite price
+            (Some(_), PostOnlyMode::Reject) | (Some(None), PostOnlyMode::Slide) => {
+                self.cancel_remainder(&order, &mut report);
+                return Ok((PostOnlyOutcome::Rejected, report));
+            }
+            (Some(Some(new_price)), PostOnlyMode::Slide) => {
+                let qty = order.get_order().get_qty();
+                if self.validate_limit_notional(&new_price, qty).is_err() {
+                    self.cancel_remainder(&order, &mut report);
+                    return Ok((PostOnlyOutcome::Rejected, report));
+                }
+                order.set_price(new_price.clone());
+                PostOnlyOutcome::Repriced(new_price)
+            }
+        };
+
+        report.set_resting(order.clone());
+        self.rest_order(order);
+        Ok((outcome, report))
+    }
+
+    /// Execute Immediate or Cancel Limit Order.
+    ///
+    /// Matches against the opposite side up to the limit price, the unfilled remainder is never inserted.
+    fn immediate_or_cancel_insert(&mut self, order: Order) -> Result<ExecutionReport, BookError> {
+        // Cancel whatever could not be matched immediately
+        self.match_and_cancel(order, true)
+    }
+
+    /// Execute Fill or Kill Limit Order.
+    ///
+    /// Checks the available liquidity up to the limit price first, without touching the orderbook.
+    /// Only executes if the whole order can be filled, otherwise the orderbook is left unchanged.
+    fn fill_or_kill_insert(&mut self, mut order: Order) -> Result<ExecutionReport, BookError> {
+        self.expire_due();
+        self.validate(&order)?;
+        self.validate_price(&mut order)?;
+        let fok_qty = order.get_order().get_qty();
+
+        let available_qty = match order.get_order_type() {
+            OrderType::Buy => self
+                .asks
+                .ask_liquidity_until(order.get_price(), fok_qty, |id| self.other_leg(id)),
+            OrderType::Sell => self
+                .bids
+                .bid_liquidity_until(order.get_price(), fok_qty, |id| self.other_leg(id)),
+        };
+        if available_qty < fok_qty {
+            // Kill, not enough liquidity to fill the whole order
+            let mut report = self.accept(&mut order);
+            self.cancel_remainder(&order, &mut report);
+            return Ok(report);
+        }
+
+        self.match_and_cancel(order, true)
+    }
+
+    /// Insert Stop Market or Stop Limit Order.
+    ///
+    /// Held in the trigger book until the last traded price reaches the stop price, the report lists the order as resting.
+    /// An order whose stop price has already been reached is triggered immediately and the report of the triggered order is returned.
+    fn stop_insert(&mut self, mut order: Order) -> Result<ExecutionReport, BookError> {
+        self.expire_due();
+        self.validate(&order)?;
+        if !matches!(
+            order.get_kind(),
+            OrderKind::StopMarket | OrderKind::StopLimit
+        ) {
+            return Err(BookError::InvalidStopOrder);
+        }
+        let stop_price = order.get_stop_price().ok_or(BookError::InvalidStopOrder)?;
+        let stop_price = self.rules.validate_price(stop_price)?;
+        if order.get_kind() == OrderKind::StopLimit {
+            self.validate_price(&mut order)?;
+        }
+        let order = order.with_stop_price(stop_price.clone());
+        let order_type = order.get_order_type();
+
+        if self.stop_triggered(order_type, &stop_price) {
+            self.emit(BookEvent::StopTriggered {
+                order_id: order.get_order().get_id(),
+                order_type,
+                stop_price,
+            });
+            return self.submit(order.triggered());
+        }
+
+        let (qty, hidden_qty) = order.get_order().split_reserve();
+        self.emit(BookEvent::StopAccepted {
+            order_id: order.get_order().get_id(),
+            order_type,
+            stop_price: stop_price.clone(),
+            qty,
+            hidden_qty,
+        });
+        let mut order = order;
+        self.stamp(&mut order);
+        if let Some(expires_at) = order.get_order().get_expires_at() {
+            self.expiries
+                .insert((expires_at, order.get_order().get_id()));
+        }
+        let mut report = ExecutionReport::new(&order);
+        report.set_resting(order.clone());
+        self.stops.insert(stop_price, order);
+        Ok(report)
+    }
+
+    /// Insert One Cancels Other Orders.
+    ///
+    /// Both orders are validated before the first one is submitted.
+    /// If the first order is executed immediately, the second order is cancelled without being submitted.
+    /// If the second order is rejected after the first one has been submitted, both are cancelled.
+    fn one_cancels_other_insert(
+        &mut self,
+        first: Order,
+        second: Order,
+        trigger: OcoTrigger,
+    ) -> Result<(ExecutionReport, ExecutionReport), BookError> {
+        self.expire_due();
+        let first_id = first.get_order().get_id();
+        let second_id = second.get_order().get_id();
+        if first_id == second_id {
+            return Err(BookError::InvalidOrderGroup);
+        }
+        self.validate_group_order(&first)?;
+        self.validate_group_order(&second)?;
+
+        self.links.insert(
+            first_id,
+            OrderLink::Oco {
+                other_id: second_id,
+                trigger,
+            },
+        );
+        self.links.insert(
+            second_id,
+            OrderLink::Oco {
+                other_id: first_id,
+                trigger,
+            },
+        );
+        let first_report = match self.submit(first) {
+            Ok(report) => report,
+            Err(error) => {
+                self.unlink(first_id);
+                return Err(error);
+            }
+        };
+        if self.links.get(&second_id) == Some(&OrderLink::OcoCancelled) {
+            self.links.remove(&second_id);
+            let second_report = self.cancel_unsubmitted(second);
+            return Ok((first_report, second_report));
+        }
+        match self.submit(second.clone()) {
+            Ok(second_report) => Ok((first_report, second_report)),
+            Err(error) => {
+                // The first order may have changed the orderbook since the validation, it must not stay open without its pair
+                debug!("OCO order {} rejected: {}", second_id, error);
+                self.unlink(second_id);
+                let mut first_report = first_report;
+                if self.cancel(first_id).is_ok() {
+                    first_report.cancel_resting();
+                }
+                let second_report = self.cancel_unsubmitted(second);
+                Ok((first_report, second_report))
+            }
+        }
+    }
+
+    /// Insert Bracket Order.
+    ///
+    /// All three orders are validated before the entry order is submitted, the exit orders are validated again once they are submitted.
+    /// If the entry order is filled immediately, the exit orders are submitted within the same call.
+    fn bracket_insert(
+        &mut self,
+        entry: Order,
+        take_profit: Order,
+        stop_loss: Order,
+    ) -> Result<ExecutionReport, BookError> {
+        self.expire_due();
+        let entry_id = entry.get_order().get_id();
+        let take_profit_id = take_profit.get_order().get_id();
+        let stop_loss_id = stop_loss.get_order().get_id();
+        let exit_side = entry.get_order_type().opposite();
+        if entry_id == take_profit_id
+            || entry_id == stop_loss_id
+            || take_profit_id == stop_loss_id
+            || take_profit.get_order_type() != exit_side
+            || stop_loss.get_order_type() != exit_side
+        {
+            return Err(BookError::InvalidOrderGroup);
+        }
+        self.validate_group_order(&entry)?;
+        self.validate_group_order(&take_profit)?;
+        self.validate_group_order(&stop_loss)?;
+
+        self.links.insert(
+            entry_id,
+            OrderLink::BracketEntry {
+                take_profit: Box::new(take_profit),
+                stop_loss: Box::new(stop_loss),
+            },
+        );
+        let report = self.submit(entry);
+        if report.is_err() {
+            self.links.remove(&entry_id);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+
+    use proptest::prelude::*;
+    use rand::Rng;
+
+    // Debug Tracing for tests
+    #[allow(dead_code)]
+    fn initialize_tracing() {
+        // Create a `LevelFilter` with the desired tracing level
+        let filter = tracing::Level::DEBUG;
+
+        // Create a `FmtSubscriber` with the filter and desired formatting options
+        let subscriber = tracing_subscriber::fmt().with_max_level(filter).finish();
+
+        // Set the subscriber as the global default
+        tracing::subscriber::set_global_default(subscriber)
+            .expect("Failed to set tracing subscriber");
+    }
+
+    proptest! {
+       #[test]
+       fn test_order_book(qty: u32, main_unit: u32, sub_unit: u8) {
+            let _order_book = fill_bids_pseudorandom();
+       }
+    }
+
+    // Pseudorandom Fill
+    fn fill_bids_pseudorandom() -> (OrderList, Vec<Order>) {
+        let mut bid_list = OrderList::default();
+        let mut remove_list = vec![];
+        let mut rng = rand::thread_rng();
+
+        for id in 0..1000 {
+            let price = rng.gen_range(1..=100) as f64;
+            let mut hasher = DefaultHasher::new();
+            hasher.write_i64(price as i64);
+            let qty = hasher.finish() % 250_000;
+            let identifiable_order = IdentifiableOrder::new(id, qty);
+            let order = Order::buy(price.into(), identifiable_order);
+            remove_list.push(order.clone());
+            bid_list.insert_order(order);
+        }
+
+        (bid_list, remove_list)
+    }
+
+    // Pseudorandom Fill
+    fn fill_asks_pseudorandom() -> (OrderList, Vec<Order>) {
+        let mut ask_list = OrderList::default();
+        let mut remove_list = vec![];
+        let mut rng = rand::thread_rng();
+
+        for id in 0..1000 {
+            let price = rng.gen_range(1..=100) as f64;
+            let mut hasher = DefaultHasher::new();
+            hasher.write_i64(price as i64);
+            let qty = hasher.finish() % 250_000;
+            let identifiable_order = IdentifiableOrder::new(id, qty);
+            let order = Order::sell(price.into(), identifiable_order);
+            remove_list.push(order.clone());
+            ask_list.insert_order(order);
+        }
+
+        (ask_list, remove_list)
+    }
+
+    // Pseudorandom Orders
+    fn generate_orders_pseudorandom(amount: u64, order_type: OrderType) -> Vec<Order> {
+        let mut orders = vec![];
+        let mut rng = rand::thread_rng();
+
+        for id in 0..amount {
+            let price = rng.gen_range(1..=100) as f64;
+            let mut hasher = DefaultHasher::new();
+            hasher.write_i64(price as i64);
+            let qty = hasher.finish() % 250_000;
+            // ids start after the ids of the pseudorandom fills
+            let identifiable_order = IdentifiableOrder::new(1000 + id, qty);
+            let order = Order::new(order_type, price.into(), identifiable_order);
+            orders.push(order);
+        }
+
+        orders
+    }
+
+    // Same order on the opposite side with an id after the ids of the pseudorandom fills
+    fn as_taker(order: Order) -> Order {
+        let identifiable_order = IdentifiableOrder::new(
+            1000 + order.get_order().get_id(),
+            order.get_order().get_qty(),
+        );
+        Order::new(
+            order.get_order_type().opposite(),
+            order.get_price().clone(),
+            identifiable_order,
+        )
+    }
+
+    // Orders are stamped with the time they are accepted, a fixed clock keeps reports comparable
+    fn fixed_clock_order_book<L: EventListener>(listener: L) -> OrderBook<L> {
+        let mut order_book = OrderBook::with_listener(listener);
+        order_book.set_clock(ManualClock::default());
+        order_book
+    }
+
+    use super::*;
+    use crate::{
+        clock::ManualClock,
+        price::{Notional, PriceError},
+        traits::event_listener::EventCollector,
+    };
+    #[test]
+    fn test_inserts() {
+        let (buy_side, _) = fill_bids_pseudorandom();
+        assert_eq!(buy_side.order_list.len(), 100);
+        let (sell_side, _) = fill_asks_pseudorandom();
+        assert_eq!(sell_side.order_list.len(), 100);
+    }
+
+    #[test]
+    fn test_inserts_remove() {
+        let (buy_side, buy_remove_list) = fill_bids_pseudorandom();
+        let (sell_side, sell_remove_list) = fill_asks_pseudorandom();
+        let order_book = OrderBook::new(buy_side, sell_side);
+        remove(order_book, buy_remove_list, sell_remove_list);
+    }
+
+    fn remove(
+        mut order_book: OrderBook,
+        buy_remove_list: Vec<Order>,
+        sell_remove_list: Vec<Order>,
+    ) {
+        debug!(
+            "Before Remove Bid Orderbook length {}",
+            order_book.bids.order_list.len()
+        );
+        for remove_order in buy_remove_list {
+            order_book.remove_order(remove_order).unwrap()
+        }
+        debug!(
+            "After Remove Bid Orderbook length {}",
+            order_book.bids.order_list.len()
+        );
+        debug!(
+            "Before Remove Ask Orderbook length {}",
+            order_book.asks.order_list.len()
+        );
+        for remove_order in sell_remove_list {
+            order_book.remove_order(remove_order).unwrap()
+        }
+        debug!(
+            "After Remove Ask Orderbook length {}",
+            order_book.asks.order_list.len()
+        );
+        assert_eq!(order_book.bids.order_list.len(), 0);
+        assert_eq!(order_book.asks.order_list.len(), 0);
+    }
+
+    #[test]
+    fn test_insert_match_remove() {
+        let (bids, buy_remove_list) = fill_bids_pseudorandom();
+        let (asks, sell_remove_list) = fill_asks_pseudorandom();
+        let mut order_book = OrderBook::new(bids, asks);
+        // Put in equivalent buy limit orders now as sell market orders
+        // Empties the orderbook completely (qty of all sell market orders == qty of all buy limit orders)
+        for order in buy_remove_list {
+            order_book.market(as_taker(order)).unwrap();
+        }
+
+        // Put in equivalent sell limit orders now as buy market orders
+        // Should empty the orderbook completely (qty of all buy market orders == qty of all sell limit orders)
+        for order in sell_remove_list {
+            order_book.market(as_taker(order)).unwrap();
+        }
+        assert_eq!(order_book.bids.order_list.len(), 0);
+        assert_eq!(order_book.asks.order_list.len(), 0);
+    }
+
+    #[test]
+    fn test_insert_random_limit_orders_remove() {
+        let (bids, _) = fill_bids_pseudorandom();
+        let orders = generate_orders_pseudorandom(1000, OrderType::Sell);
+        let mut order_book = OrderBook::new(bids, OrderList::default());
+
+        for order in orders {
+            order_book.match_and_insert(order).unwrap();
+        }
+        println!("{}", order_book);
+    }
+
+    /*
+        Market Buy Tests
+    */
+
+    /// Market Buy Test with randomly filled orderbook
+    #[test]
+    fn test_market_buy_random() {
+        let (sell_side, _) = fill_asks_pseudorandom();
+        let mut order_book = OrderBook::new(OrderList::default(), sell_side);
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::buy(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 512));
+    }
+
+    /// Normal Market Buy Test.
+    #[test]
+    fn test_market_buy_normal() {
+        let mut order_book = OrderBook::default();
+        // Fill orderbook
+        for i in 1..=10 {
+            let price = Price::new(i, 0);
+            let qty = 100;
+            let identifiable_order = IdentifiableOrder::new(i as u64, qty);
+            let order = Order::sell(price, identifiable_order);
+            order_book.insert_order(order).unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::buy(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 512));
+    }
+
+    /// Market Buy Full Fill Test
+    #[test]
+    fn test_market_buy_full_fill() {
+        let mut order_book = OrderBook::default();
+        // Fill orderbook
+        for i in 1..=5 {
+            let price = Price::new(i, 0);
+            let qty = 100;
+            let identifiable_order = IdentifiableOrder::new(i as u64, qty);
+            let order = Order::sell(price, identifiable_order);
+            order_book.insert_order(order).unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(1000, 500);
+        let result = order_book
+            .market(Order::buy(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (500, 500));
+    }
+
+    /// Market Buy more than Orderbook has test
+    #[test]
+    fn test_market_buy_exceeding_orderbook() {
+        let mut order_book = OrderBook::default();
+        // Fill orderbook
+        for i in 1..=5 {
+            let price = Price::new(i, 0);
+            let qty = 100;
+            let identifiable_order = IdentifiableOrder::new(i as u64, qty);
+            let order = Order::sell(price, identifiable_order);
+            order_book.insert_order(order).unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::buy(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 500));
+    }
+
+    /// Orderbook Empty Market Buy Test
+    #[test]
+    fn test_market_buy_empty_book() {
+        let mut order_book = OrderBook::default();
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::buy(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 0));
+        assert_eq!(result.get_cancelled_qty(), 512);
+    }
+
+    /*
+        Market Sell Tests
+    */
+
+    /// Market Sell Test with randomly filled orderbook
+    #[test]
+    fn test_market_sell_random() {
+        let (buy_side, _) = fill_bids_pseudorandom();
+        let mut order_book = OrderBook::new(buy_side, OrderList::default());
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::sell(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 512));
+    }
+
+    /// Normal Market Sell Test.
+    #[test]
+    fn test_market_sell_normal() {
+        let mut order_book = OrderBook::default();
+        // Fill orderbook
+        for i in 1..=10 {
+            let price = Price::new(i, 0);
+            let qty = 100;
+            let identifiable_order = IdentifiableOrder::new(i as u64, qty);
+            let order = Order::buy(price, identifiable_order);
+            order_book.insert_order(order).unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::sell(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 512));
+    }
+
+    /// Market Sell Full Fill Test
+    #[test]
+    fn test_market_sell_full_fill() {
+        //initialize_tracing();
+        let mut order_book = OrderBook::default();
+        // Fill orderbook
+        for i in 1..=5 {
+            let price = Price::new(i, 0);
+            let qty = 100;
+            let identifiable_order = IdentifiableOrder::new(i as u64, qty);
+            let order = Order::buy(price, identifiable_order);
+            order_book.insert_order(order).unwrap();
+        }
+        debug!("Created Orderbook: {:?}", order_book);
+        let identifiable_order = IdentifiableOrder::new(1000, 500);
+        let result = order_book
+            .market(Order::sell(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (500, 500));
+    }
+
+    /// Market Buy more than Orderbook has test
+    #[test]
+    fn test_market_sell_exceeding_orderbook() {
+        let mut order_book = OrderBook::default();
+        // Fill orderbook
+        for i in 1..=5 {
+            let price = Price::new(i, 0);
+            let qty = 100;
+            let identifiable_order = IdentifiableOrder::new(i as u64, qty);
+            let order = Order::buy(price, identifiable_order);
+            order_book.insert_order(order).unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::sell(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 500));
+    }
+
+    /// Orderbook Empty Market Buy Test
+    #[test]
+    fn test_market_sell_empty_book() {
+        let mut order_book = OrderBook::default();
+        let identifiable_order = IdentifiableOrder::new(1000, 512);
+        let result = order_book
+            .market(Order::sell(Price::new(1, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (512, 0));
+        assert_eq!(result.get_cancelled_qty(), 512);
+    }
+
+    /*
+        Immediate or Cancel Tests
+    */
+
+    /// IOC Buy partially fills up to the limit price and cancels the rest
+    #[test]
+    fn test_immediate_or_cancel_buy_partial() {
+        let mut order_book = OrderBook::default();
+        for i in 1..=5 {
+            let identifiable_order = IdentifiableOrder::new(i as u64, 100);
+            order_book
+                .insert_order(Order::sell(Price::new(i, 0), identifiable_order))
+                .unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(10, 350);
+        let result = order_book
+            .immediate_or_cancel_insert(Order::buy(Price::new(3, 0), identifiable_order))
+            .unwrap();
+        assert_eq!(
+            (result.get_filled_qty(), result.get_cancelled_qty()),
+            (300, 50)
+        );
+        // Remainder must not rest on the buy side
+        assert_eq!(order_book.bids.order_list.len(), 0);
+        assert_eq!(order_book.asks.order_list.len(), 2);
+    }
+
+    /// IOC Sell without crossing liquidity cancels everything
+    #[test]
+    fn test_immediate_or_cancel_sell_no_match() {
+        let mut order_book = OrderBook::default();
+        let identifiable_order = IdentifiableOrder::new(1, 100);
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), identifiable_order))
+            .unwrap();
+
+        let identifiable_order = IdentifiableOrder::new(2, 100);
+        let result = order_book
+            .immediate_or_cancel_insert(Order::sell(Price::new(2, 0), identifiable_order))
+            .unwrap();
+        assert_eq!(
+            (result.get_filled_qty(), result.get_cancelled_qty()),
+            (0, 100)
+        );
+        assert_eq!(order_book.bids.order_list.len(), 1);
+        assert_eq!(order_book.asks.order_list.len(), 0);
+    }
+
+    /// IOC Sell fully filled leaves nothing to cancel
+    #[test]
+    fn test_immediate_or_cancel_sell_full_fill() {
+        let mut order_book = OrderBook::default();
+        for i in 1..=5 {
+            let identifiable_order = IdentifiableOrder::new(i as u64, 100);
+            order_book
+                .insert_order(Order::buy(Price::new(i, 0), identifiable_order))
+                .unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(10, 250);
+        let result = order_book
+            .immediate_or_cancel_insert(Order::sell(Price::new(3, 0), identifiable_order))
+            .unwrap();
+        assert_eq!(
+            (result.get_filled_qty(), result.get_cancelled_qty()),
+            (250, 0)
+        );
+        assert_eq!(order_book.bids.order_list.len(), 3);
+        assert_eq!(order_book.asks.order_list.len(), 0);
+    }
+
+    /*
+        Fill or Kill Tests
+    */
+
+    /// FOK Buy with enough liquidity up to the limit price is completely filled
+    #[test]
+    fn test_fill_or_kill_buy_filled() {
+        let mut order_book = OrderBook::default();
+        for i in 1..=5 {
+            let identifiable_order = IdentifiableOrder::new(i as u64, 100);
+            order_book
+                .insert_order(Order::sell(Price::new(i, 0), identifiable_order))
+                .unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(10, 250);
+        let result = order_book
+            .fill_or_kill_insert(Order::buy(Price::new(3, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (250, 250));
+        assert_eq!(order_book.bids.order_list.len(), 0);
+        assert_eq!(order_book.asks.order_list.len(), 3);
+    }
+
+    /// FOK Buy without enough liquidity up to the limit price leaves the orderbook unchanged
+    #[test]
+    fn test_fill_or_kill_buy_killed() {
+        let mut order_book = OrderBook::default();
+        for i in 1..=5 {
+            let identifiable_order = IdentifiableOrder::new(i as u64, 100);
+            order_book
+                .insert_order(Order::sell(Price::new(i, 0), identifiable_order))
+                .unwrap();
+        }
+        let before = format!("{:?}", order_book);
+
+        let identifiable_order = IdentifiableOrder::new(10, 301);
+        let result = order_book
+            .fill_or_kill_insert(Order::buy(Price::new(3, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (301, 0));
+        assert_eq!(result.get_cancelled_qty(), 301);
+        assert_eq!(format!("{:?}", order_book), before);
+    }
+
+    /// FOK Sell exactly matching the available liquidity is completely filled
+    #[test]
+    fn test_fill_or_kill_sell_exact() {
+        let mut order_book = OrderBook::default();
+        for i in 1..=5 {
+            let identifiable_order = IdentifiableOrder::new(i as u64, 100);
+            order_book
+                .insert_order(Order::buy(Price::new(i, 0), identifiable_order))
+                .unwrap();
+        }
+
+        let identifiable_order = IdentifiableOrder::new(10, 300);
+        let result = order_book
+            .fill_or_kill_insert(Order::sell(Price::new(3, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (300, 300));
+        assert_eq!(order_book.bids.order_list.len(), 2);
+    }
+
+    /// FOK Sell on an empty orderbook is killed
+    #[test]
+    fn test_fill_or_kill_sell_empty_book() {
+        let mut order_book = OrderBook::default();
+        let identifiable_order = IdentifiableOrder::new(10, 300);
+        let result = order_book
+            .fill_or_kill_insert(Order::sell(Price::new(3, 0), identifiable_order))
+            .unwrap();
+        assert_eq!((result.get_qty(), result.get_filled_qty()), (300, 0));
+        assert_eq!(result.get_cancelled_qty(), 300);
+    }
+
+    /*
+        Post Only (Limit or Cancel) Tests
+    */
+
+    fn post_only_order_book() -> OrderBook {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::buy(Price::new(9, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        order_book
+    }
+
+    /// Post only order inside the spread is accepted and rests on the book
+    #[test]
+    fn test_post_only_accepted() {
+        let mut order_book = post_only_order_book();
+        let (result, _) = order_book
+            .limit_or_cancel_insert(
+                Order::buy(Price::new(9, 50), IdentifiableOrder::new(3, 100)),
+                PostOnlyMode::Reject,
+            )
+            .unwrap();
+        assert_eq!(result, PostOnlyOutcome::Accepted);
+        assert_eq!(order_book.get_price(), Some(&Price::new(9, 50)));
+    }
+
+    /// Post only order crossing the spread is rejected and does not match
+    #[test]
+    fn test_post_only_rejected() {
+        let mut order_book = post_only_order_book();
+        let before = format!("{:?}", order_book);
+        let (result, _) = order_book
+            .limit_or_cancel_insert(
+                Order::sell(Price::new(9, 0), IdentifiableOrder::new(3, 100)),
+                PostOnlyMode::Reject,
+            )
+            .unwrap();
+        assert_eq!(result, PostOnlyOutcome::Rejected);
+        assert_eq!(format!("{:?}", order_book), before);
+    }
+
+    /// Post only order crossing the spread slides one tick behind the best opposite price
+    #[test]
+    fn test_post_only_repriced() {
+        let mut order_book = post_only_order_book();
+        let (result, _) = order_book
+            .limit_or_cancel_insert(
+                Order::buy(Price::new(11, 0), IdentifiableOrder::new(3, 100)),
+                PostOnlyMode::Slide,
+            )
+            .unwrap();
+        assert_eq!(result, PostOnlyOutcome::Repriced(Price::new(9, 99)));
+        assert_eq!(order_book.asks.order_list.len(), 1);
+        assert_eq!(order_book.bids.order_list.len(), 2);
+
+        let (result, _) = order_book
+            .limit_or_cancel_insert(
+                Order::sell(Price::new(9, 0), IdentifiableOrder::new(4, 100)),
+                PostOnlyMode::Slide,
+            )
+            .unwrap();
+        assert_eq!(result, PostOnlyOutcome::Repriced(Price::new(10, 0)));
+        assert_eq!(order_book.asks.order_list.len(), 1);
+    }
+
+    /*
+        Execution Report Tests
+    */
+
+    /// Every hit maker order is reported with its price and filled quantity
+    #[test]
+    fn test_execution_report_fills() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(2, 0),
+                IdentifiableOrder::new(3, 100),
+            ))
+            .unwrap();
+
+        let report = order_book
+            .match_and_insert(Order::buy(
+                Price::new(2, 0),
+                IdentifiableOrder::new(10, 250),
+            ))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[
+                Fill::new(1, 10, Price::new(1, 0), 100),
+                Fill::new(2, 10, Price::new(1, 0), 100),
+                Fill::new(3, 10, Price::new(2, 0), 50),
+            ]
+        );
+        assert!(report.is_filled());
+        assert_eq!(report.get_resting(), None);
+        assert_eq!(report.get_cancelled_qty(), 0);
+    }
+
+    /// The unfilled remainder of a limit order is reported as resting
+    #[test]
+    fn test_execution_report_resting() {
+        let mut order_book = fixed_clock_order_book(NoopListener);
+        order_book
+            .insert_order(Order::buy(Price::new(2, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+
+        let report = order_book
+            .match_and_insert(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 250),
+            ))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[Fill::new(1, 10, Price::new(2, 0), 100)]
+        );
+        assert_eq!(
+            report.get_resting(),
+            Some(&Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 150)
+            ))
+        );
+        assert_eq!(report.get_resting_qty(), 150);
+        assert_eq!(report.get_cancelled_qty(), 0);
+        assert_eq!(order_book.asks.order_list.len(), 1);
+        assert_eq!(order_book.bids.order_list.len(), 0);
+    }
+
+    /*
+        Event Listener Tests
+    */
+
+    /// Matching a resting order fires fill, price level and rest events in order
+    #[test]
+    fn test_events_match_and_insert() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(2, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        order_book.get_listener_mut().take_events();
+
+        order_book
+            .match_and_insert(Order::buy(
+                Price::new(1, 50),
+                IdentifiableOrder::new(10, 150),
+            ))
+            .unwrap();
+        assert_eq!(
+            order_book.get_listener().get_events(),
+            &[
+                BookEvent::OrderAccepted {
+                    order_id: 10,
+                    order_type: OrderType::Buy,
+                    price: Price::new(1, 50),
+                    qty: 150,
+                    hidden_qty: 0,
+                },
+                BookEvent::OrderFilled {
+                    order_id: 1,
+                    order_type: OrderType::Sell,
+                    counterparty_id: 10,
+                    price: Price::new(1, 0),
+                    qty: 100,
+                },
+                BookEvent::OrderPartiallyFilled {
+                    order_id: 10,
+                    order_type: OrderType::Buy,
+                    counterparty_id: 1,
+                    price: Price::new(1, 0),
+                    qty: 100,
+                    remaining_qty: 50,
+                    hidden_qty: 0,
+                },
+                BookEvent::PriceLevelRemoved {
+                    order_type: OrderType::Sell,
+                    price: Price::new(1, 0),
+                },
+                BookEvent::LevelUpdated(LevelUpdate::new(
+                    3,
+                    OrderType::Sell,
+                    PriceLevel::new(Price::new(1, 0), 0, 0),
+                )),
+                BookEvent::PriceLevelAdded {
+                    order_type: OrderType::Buy,
+                    price: Price::new(1, 50),
+                },
+                BookEvent::OrderRested {
+                    order_id: 10,
+                    order_type: OrderType::Buy,
+                    price: Price::new(1, 50),
+                    qty: 50,
+                    hidden_qty: 0,
+                },
+                BookEvent::LevelUpdated(LevelUpdate::new(
+                    4,
+                    OrderType::Buy,
+                    PriceLevel::new(Price::new(1, 50), 50, 1),
+                )),
+            ]
+        );
+    }
+
+    /// Unfilled IOC remainder and removed orders fire cancel events
+    #[test]
+    fn test_events_cancel() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(2, 100)))
+            .unwrap();
+        order_book.get_listener_mut().take_events();
+
+        order_book
+            .remove_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+        order_book
+            .immediate_or_cancel_insert(Order::sell(
+                Price::new(2, 0),
+                IdentifiableOrder::new(10, 50),
+            ))
+            .unwrap();
+        order_book
+            .remove_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(2, 100)))
+            .unwrap();
+        assert_eq!(
+            order_book.get_listener_mut().take_events(),
+            vec![
+                BookEvent::OrderCancelled {
+                    order_id: 1,
+                    order_type: OrderType::Buy,
+                    qty: 100,
+                    hidden_qty: 0,
+                },
+                BookEvent::LevelUpdated(LevelUpdate::new(
+                    3,
+                    OrderType::Buy,
+                    PriceLevel::new(Price::new(1, 0), 100, 1),
+                )),
+                BookEvent::OrderAccepted {
+                    order_id: 10,
+                    order_type: OrderType::Sell,
+                    price: Price::new(2, 0),
+                    qty: 50,
+                    hidden_qty: 0,
+                },
+                BookEvent::OrderCancelled {
+                    order_id: 10,
+                    order_type: OrderType::Sell,
+                    qty: 50,
+                    hidden_qty: 0,
+                },
+                BookEvent::OrderCancelled {
+                    order_id: 2,
+                    order_type: OrderType::Buy,
+                    qty: 100,
+                    hidden_qty: 0,
+                },
+                BookEvent::PriceLevelRemoved {
+                    order_type: OrderType::Buy,
+                    price: Price::new(1, 0),
+                },
+                BookEvent::LevelUpdated(LevelUpdate::new(
+                    4,
+                    OrderType::Buy,
+                    PriceLevel::new(Price::new(1, 0), 0, 0),
+                )),
+            ]
+        );
+    }
+
+    /*
+        Cancel Tests
+    */
+
+    /// Removing a price level cancels each of its orders before the level is removed
+    #[test]
+    fn test_remove_price_level_events() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book
+            .one_cancels_other_insert(
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(1, 5)),
+                Order::sell(Price::new(11, 0), IdentifiableOrder::new(2, 5)),
+                OcoTrigger::Fill,
+            )
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(3, 20).with_display_qty(5),
+            ))
+            .unwrap();
+        let sequence = order_book.get_sequence();
+        order_book.get_listener_mut().take_events();
+
+        let removed = order_book.remove_ask_price_level(&Price::new(10, 0));
+        assert_eq!(removed.map(|orders| orders.len()), Some(2));
+        assert_eq!(
+            order_book.get_listener().get_events(),
+            &[
+                BookEvent::OrderCancelled {
+                    order_id: 1,
+                    order_type: OrderType::Sell,
+                    qty: 5,
+                    hidden_qty: 0,
+                },
+                BookEvent::OrderCancelled {
+                    order_id: 3,
+                    order_type: OrderType::Sell,
+                    qty: 5,
+                    hidden_qty: 15,
+                },
+                BookEvent::PriceLevelRemoved {
+                    order_type: OrderType::Sell,
+                    price: Price::new(10, 0),
+                },
+                BookEvent::LevelUpdated(LevelUpdate::new(
+                    sequence + 1,
+                    OrderType::Sell,
+                    PriceLevel::new(Price::new(10, 0), 0, 0),
+                )),
+            ]
+        );
+        assert!(order_book.links.is_empty());
+        assert_eq!(order_book.remove_ask_price_level(&Price::new(10, 0)), None);
+        assert_eq!(order_book.get_sequence(), sequence + 1);
+    }
+
+    /// Cancel by id after a partial fill removes the order with its remaining quantity
+    #[test]
+    fn test_cancel_after_partial_fill() {
+        let mut order_book = fixed_clock_order_book(EventCollector::default());
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 40)))
+            .unwrap();
+        order_book.get_listener_mut().take_events();
+
+        let cancelled = order_book.cancel(1).unwrap();
+        assert_eq!(cancelled.get_order_type(), OrderType::Sell);
+        assert_eq!(
+            cancelled.get_order(),
+            &Order::sell(Price::new(1, 0), IdentifiableOrder::new(1, 60))
+        );
+        assert_eq!(order_book.asks.order_list.len(), 0);
+        assert_eq!(
+            order_book.get_listener().get_events(),
+            &[
+                BookEvent::OrderCancelled {
+                    order_id: 1,
+                    order_type: OrderType::Sell,
+                    qty: 60,
+                    hidden_qty: 0,
+                },
+                BookEvent::PriceLevelRemoved {
+                    order_type: OrderType::Sell,
+                    price: Price::new(1, 0),
+                },
+                BookEvent::LevelUpdated(LevelUpdate::new(
+                    3,
+                    OrderType::Sell,
+                    PriceLevel::new(Price::new(1, 0), 0, 0),
+                )),
+            ]
+        );
+        assert_eq!(order_book.cancel(1), Err(BookError::UnknownOrder(1)));
+    }
+
+    /// Cancels in the middle of a FIFO queue keep the other orders cancellable
+    #[test]
+    fn test_cancel_fifo_queue() {
+        let mut order_book = OrderBook::default();
+        for id in 1..=5 {
+            order_book
+                .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(id, 10)))
+                .unwrap();
+        }
+        assert!(order_book.cancel(2).is_ok());
+        assert!(order_book.cancel(4).is_ok());
+        // Filled orders are no longer cancellable
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 10),
+            ))
+            .unwrap();
+        assert_eq!(order_book.cancel(1), Err(BookError::UnknownOrder(1)));
+        assert!(order_book.cancel(5).is_ok());
+        assert!(order_book.cancel(3).is_ok());
+        assert_eq!(order_book.bids.order_list.len(), 0);
+    }
+
+    /// Every order of a randomly filled orderbook can be cancelled by its id
+    #[test]
+    fn test_cancel_random() {
+        let (bids, bid_orders) = fill_bids_pseudorandom();
+        let mut order_book = OrderBook::new(bids, OrderList::default());
+        for order in bid_orders.iter().rev() {
+            let cancelled = order_book.cancel(order.get_order().get_id()).unwrap();
+            assert_eq!(cancelled.get_order(), order);
+        }
+        assert_eq!(order_book.bids.order_list.len(), 0);
+    }
+
+    #[test]
+    fn test_cancel_unknown_order() {
+        let mut order_book = OrderBook::default();
+        assert_eq!(order_book.cancel(42), Err(BookError::UnknownOrder(42)));
+    }
+
+    /*
+        Amend Tests
+    */
+
+    /// Reducing the quantity keeps the time priority
+    #[test]
+    fn test_amend_reduce_keeps_priority() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+
+        let report = order_book.amend(1, 50, Price::new(1, 0)).unwrap();
+        assert_eq!(report.get_resting_qty(), 50);
+
+        let report = order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 50)))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[Fill::new(1, 10, Price::new(1, 0), 50)]
+        );
+    }
+
+    /// Increasing the quantity moves the order to the back of the FIFO queue
+    #[test]
+    fn test_amend_increase_loses_priority() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+
+        let report = order_book.amend(1, 150, Price::new(1, 0)).unwrap();
+        assert_eq!(report.get_resting_qty(), 150);
+
+        let report = order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 150),
+            ))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[
+                Fill::new(2, 10, Price::new(1, 0), 100),
+                Fill::new(1, 10, Price::new(1, 0), 50),
+            ]
+        );
+    }
+
+    /// Repricing across the spread matches the order first, the remainder rests at the new price
+    #[test]
+    fn test_amend_reprice_crossing() {
+        let mut order_book = fixed_clock_order_book(NoopListener);
+        order_book
+            .insert_order(Order::sell(Price::new(2, 0), IdentifiableOrder::new(1, 50)))
+            .unwrap();
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(2, 100)))
+            .unwrap();
+
+        let report = order_book.amend(2, 100, Price::new(2, 0)).unwrap();
+        assert_eq!(report.get_fills(), &[Fill::new(1, 2, Price::new(2, 0), 50)]);
+        assert_eq!(
+            report.get_resting(),
+            Some(&Order::buy(Price::new(2, 0), IdentifiableOrder::new(2, 50)))
+        );
+        assert_eq!(order_book.get_price(), Some(&Price::new(2, 0)));
+        assert_eq!(order_book.asks.order_list.len(), 0);
+        assert_eq!(order_book.bids.order_list.len(), 1);
+        // Order is still known under its id
+        assert!(order_book.cancel(2).is_ok());
+    }
+
+    /// Amending to zero cancels the order, unknown orders can not be amended
+    #[test]
+    fn test_amend_zero_and_unknown() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+
+        let report = order_book.amend(1, 0, Price::new(1, 0)).unwrap();
+        assert_eq!(report.get_cancelled_qty(), 100);
+        assert_eq!(order_book.bids.order_list.len(), 0);
+        assert_eq!(
+            order_book.amend(1, 10, Price::new(1, 0)),
+            Err(BookError::UnknownOrder(1))
+        );
+    }
+
+    /*
+        Error Tests
+    */
+
+    /// Zero quantity orders are rejected on every entry point without touching the orderbook
+    #[test]
+    fn test_error_zero_quantity() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        let order = Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 0));
+        assert_eq!(
+            order_book.insert_order(order.clone()),
+            Err(BookError::ZeroQuantity)
+        );
+        assert_eq!(
+            order_book.market(order.clone()),
+            Err(BookError::ZeroQuantity)
+        );
+        assert_eq!(
+            order_book.match_and_insert(order.clone()),
+            Err(BookError::ZeroQuantity)
+        );
+        assert_eq!(
+            order_book.limit_or_cancel_insert(order.clone(), PostOnlyMode::Reject),
+            Err(BookError::ZeroQuantity)
+        );
+        assert_eq!(
+            order_book.immediate_or_cancel_insert(order.clone()),
+            Err(BookError::ZeroQuantity)
+        );
+        assert_eq!(
+            order_book.fill_or_kill_insert(order),
+            Err(BookError::ZeroQuantity)
+        );
+        assert!(order_book.get_listener().get_events().is_empty());
+        assert_eq!(order_book.bids.order_list.len(), 0);
+    }
+
+    /// Direct inserts must not cross the spread
+    #[test]
+    fn test_error_crossed_book() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(2, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        assert_eq!(
+            order_book.insert_order(Order::buy(Price::new(2, 0), IdentifiableOrder::new(2, 100))),
+            Err(BookError::CrossedBook)
+        );
+        order_book
+            .insert_order(Order::buy(
+                Price::new(1, 99),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        assert_eq!(
+            order_book.insert_order(Order::sell(
+                Price::new(1, 50),
+                IdentifiableOrder::new(3, 100)
+            )),
+            Err(BookError::CrossedBook)
+        );
+    }
+
+    /// Removing an order which is not in the orderbook fails
+    #[test]
+    fn test_error_remove_unknown_order() {
+        let mut order_book = OrderBook::default();
+        let order = Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100));
+        order_book.insert_order(order.clone()).unwrap();
+        // Same order on the wrong side
+        assert_eq!(
+            order_book.remove_order(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(1, 100)
+            )),
+            Err(BookError::UnknownOrder(1))
+        );
+        assert_eq!(order_book.remove_order(order.clone()), Ok(()));
+        assert_eq!(
+            order_book.remove_order(order),
+            Err(BookError::UnknownOrder(1))
+        );
+    }
+
+    /// Orders with the id of a resting order are rejected
+    #[test]
+    fn test_error_duplicate_resting_order() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+        assert_eq!(
+            order_book
+                .match_and_insert(Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100))),
+            Err(BookError::DuplicateOrderId(1))
+        );
+    }
+
+    /*
+        Order Id Tests
+    */
+
+    /// Order ids of resting orders are unique across both sides and all entry points
+    #[test]
+    fn test_duplicate_order_id() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+        let duplicate = Order::sell(Price::new(2, 0), IdentifiableOrder::new(1, 100));
+        assert_eq!(
+            order_book.insert_order(duplicate.clone()),
+            Err(BookError::DuplicateOrderId(1))
+        );
+        assert_eq!(
+            order_book.market(duplicate.clone()),
+            Err(BookError::DuplicateOrderId(1))
+        );
+        assert_eq!(
+            order_book.immediate_or_cancel_insert(duplicate.clone()),
+            Err(BookError::DuplicateOrderId(1))
+        );
+        assert_eq!(
+            order_book.fill_or_kill_insert(duplicate.clone()),
+            Err(BookError::DuplicateOrderId(1))
+        );
+        assert_eq!(
+            order_book.limit_or_cancel_insert(duplicate, PostOnlyMode::Reject),
+            Err(BookError::DuplicateOrderId(1))
+        );
+        // Nothing has been matched
+        assert_eq!(order_book.bids.order_list.len(), 1);
+
+        // Id can be reused after the order left the orderbook
+        order_book.cancel(1).unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(2, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+    }
+
+    /// Generated order ids are monotonic and skip ids of resting orders
+    #[test]
+    fn test_next_order_id() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::buy(Price::new(1, 0), IdentifiableOrder::new(2, 100)))
+            .unwrap();
+        assert_eq!(order_book.next_order_id(), Ok(1));
+        assert_eq!(order_book.next_order_id(), Ok(3));
+
+        order_book.set_order_id_generator(OrderIdGenerator::new(100));
+        let order_id = order_book.next_order_id().unwrap();
+        assert_eq!(order_id, 100);
+        order_book
+            .insert_order(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(order_id, 100),
+            ))
+            .unwrap();
+        assert_eq!(order_book.next_order_id(), Ok(101));
+    }
+
+    /// The last id is returned once, afterwards the sequence is exhausted
+    #[test]
+    fn test_next_order_id_exhausted() {
+        let mut order_book = OrderBook::default();
+        order_book.set_order_id_generator(OrderIdGenerator::new(u64::MAX));
+        assert_eq!(order_book.next_order_id(), Ok(u64::MAX));
+        assert_eq!(order_book.next_order_id(), Err(BookError::IdsExhausted));
+
+        // Resting order with the last id is skipped without looping forever
+        order_book
+            .insert_order(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(u64::MAX, 100),
+            ))
+            .unwrap();
+        order_book.set_order_id_generator(OrderIdGenerator::new(u64::MAX));
+        assert_eq!(order_book.next_order_id(), Err(BookError::IdsExhausted));
+    }
+
+    /* Submit Tests */
+
+    /// Orders are routed by their kind and time in force
+    #[test]
+    fn test_submit_routing() {
+        let mut order_book = post_only_order_book();
+
+        // Good till Cancel rests on the book
+        let report = order_book
+            .submit(Order::buy(
+                Price::new(9, 50),
+                IdentifiableOrder::new(3, 100),
+            ))
+            .unwrap();
+        assert_eq!(report.get_resting_qty(), 100);
+
+        // Immediate or Cancel never rests
+        let report = order_book
+            .submit(
+                Order::sell(Price::new(9, 50), IdentifiableOrder::new(4, 150))
+                    .with_time_in_force(TimeInForce::ImmediateOrCancel),
+            )
+            .unwrap();
+        assert_eq!(
+            (report.get_filled_qty(), report.get_cancelled_qty()),
+            (100, 50)
+        );
+
+        // Fill or Kill is killed without enough liquidity
+        let report = order_book
+            .submit(
+                Order::buy(Price::new(10, 0), IdentifiableOrder::new(5, 150))
+                    .with_time_in_force(TimeInForce::FillOrKill),
+            )
+            .unwrap();
+        assert_eq!(report.get_cancelled_qty(), 150);
+
+        // Post only slides behind the best ask
+        let report = order_book
+            .submit(
+                Order::buy(Price::new(10, 0), IdentifiableOrder::new(6, 100))
+                    .with_time_in_force(TimeInForce::PostOnly(PostOnlyMode::Slide)),
+            )
+            .unwrap();
+        assert_eq!(
+            report.get_resting().map(Order::get_price),
+            Some(&Price::new(9, 99))
+        );
+
+        // Market orders ignore their price
+        let report = order_book
+            .submit(
+                Order::buy(Price::new(1, 0), IdentifiableOrder::new(7, 100))
+                    .with_kind(OrderKind::Market),
+            )
+            .unwrap();
+        assert!(report.is_filled());
+        assert_eq!(order_book.asks.order_list.len(), 0);
+    }
+
+    /// Fill events report the side of maker and taker
+    #[test]
+    fn test_submit_events_report_side() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book
+            .submit(Order::buy(Price::new(1, 0), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+        order_book
+            .submit(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        let sides: Vec<_> = order_book
+            .get_listener()
+            .get_events()
+            .iter()
+            .filter_map(|event| match event {
+                BookEvent::OrderFilled {
+                    order_id,
+                    order_type,
+                    ..
+                } => Some((*order_id, *order_type)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sides, vec![(1, OrderType::Buy), (2, OrderType::Sell)]);
+    }
+
+    /* Depth Tests */
+
+    /// Depth aggregates quantity and order count per level, best price first
+    #[test]
+    fn test_depth() {
+        let mut order_book = OrderBook::default();
+        for (i, (price, qty)) in [(1, 100), (2, 50), (2, 25), (3, 10)]
+            .into_iter()
+            .enumerate()
+        {
+            order_book
+                .insert_order(Order::buy(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(i as u64, qty),
+                ))
+                .unwrap();
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(price + 10, 0),
+                    IdentifiableOrder::new(i as u64 + 10, qty),
+                ))
+                .unwrap();
+        }
+
+        let depth = order_book.depth(2);
+        assert_eq!(
+            depth.get_bids(),
+            &[
+                PriceLevel::new(Price::new(3, 0), 10, 1),
+                PriceLevel::new(Price::new(2, 0), 75, 2),
+            ]
+        );
+        assert_eq!(
+            depth.get_asks(),
+            &[
+                PriceLevel::new(Price::new(11, 0), 100, 1),
+                PriceLevel::new(Price::new(12, 0), 75, 2),
+            ]
+        );
+
+        // Partially filled orders are aggregated with their open quantity
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(20, 30),
+            ))
+            .unwrap();
+        assert_eq!(
+            order_book.depth(10).get_bids(),
+            &[
+                PriceLevel::new(Price::new(2, 0), 55, 2),
+                PriceLevel::new(Price::new(1, 0), 100, 1),
+            ]
+        );
+        assert_eq!(
+            order_book.depth(0),
+            Depth::new(order_book.get_sequence(), vec![], vec![])
+        );
+        assert_eq!(OrderBook::default().depth(5), Depth::default());
+    }
+
+    /* Snapshot Tests */
+
+    /// Resting orders are listed in price/time priority with their queue position
+    #[test]
+    fn test_iter_priority_order() {
+        let mut order_book = OrderBook::default();
+        for (order_id, price) in [(1, 1), (2, 2), (3, 1), (4, 2)] {
+            order_book
+                .insert_order(Order::buy(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(price + 10, 0),
+                    IdentifiableOrder::new(order_id + 10, 100),
+                ))
+                .unwrap();
+        }
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(20, 40),
+            ))
+            .unwrap();
+
+        let bids: Vec<_> = order_book.iter_bids().collect();
+        assert_eq!(
+            bids,
+            vec![
+                RestingOrder::new(2, OrderType::Buy, Price::new(2, 0), 60, 0),
+                RestingOrder::new(4, OrderType::Buy, Price::new(2, 0), 100, 1),
+                RestingOrder::new(1, OrderType::Buy, Price::new(1, 0), 100, 0),
+                RestingOrder::new(3, OrderType::Buy, Price::new(1, 0), 100, 1),
+            ]
+        );
+        let ask_ids: Vec<_> = order_book
+            .iter_asks()
+            .map(|order| order.get_order_id())
+            .collect();
+        assert_eq!(ask_ids, vec![11, 13, 12, 14]);
+
+        let snapshot = order_book.snapshot();
+        assert_eq!(snapshot.get_bids(), &bids[..]);
+        assert_eq!(snapshot.get_asks().len(), 4);
+    }
+
+    /// Cancelled orders disappear from the snapshot and move the queue up
+    #[test]
+    fn test_snapshot_after_cancel() {
+        let mut order_book = OrderBook::default();
+        for order_id in 1..=3 {
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(1, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
+        }
+        order_book.cancel(1).unwrap();
+        assert_eq!(
+            order_book.snapshot(),
+            Snapshot::new(
+                vec![],
+                vec![
+                    RestingOrder::new(2, OrderType::Sell, Price::new(1, 0), 100, 0),
+                    RestingOrder::new(3, OrderType::Sell, Price::new(1, 0), 100, 1),
+                ]
+            )
+        );
+        assert_eq!(OrderBook::default().snapshot(), Snapshot::default());
+    }
+
+    /* Top of Book Tests */
+
+    /// Best levels, spread and mid price of both sides
+    #[test]
+    fn test_top_of_book() {
+        let mut order_book = OrderBook::default();
+        assert_eq!(order_book.best_bid(), None);
+        assert_eq!(order_book.spread(), None);
+        assert_eq!(order_book.mid_price(), None);
+        assert_eq!(order_book.microprice(), None);
+
+        for order in [
+            Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, 300)),
+            Order::buy(Price::new(10, 0), IdentifiableOrder::new(2, 100)),
+            Order::buy(Price::new(9, 0), IdentifiableOrder::new(3, 100)),
+            Order::sell(Price::new(10, 1), IdentifiableOrder::new(4, 100)),
+        ] {
+            order_book.insert_order(order).unwrap();
+        }
+
+        assert_eq!(
+            order_book.best_bid(),
+            Some(PriceLevel::new(Price::new(10, 0), 400, 2))
+        );
+        assert_eq!(
+            order_book.best_ask(),
+            Some(PriceLevel::new(Price::new(10, 1), 100, 1))
+        );
+        assert_eq!(order_book.spread(), Some(Price::new(0, 1)));
+        assert_eq!(order_book.mid_price().unwrap().to_string(), "10.005");
+        // 4x the quantity on the bid pulls the microprice towards the ask
+        let microprice = order_book.microprice().unwrap();
+        assert!((microprice - 10.008).abs() < 1e-9);
+    }
+
+    /// Last traded price follows fills, not resting orders
     #[test]
-    fn test_inserts_remove() {
-        let (buy_side, buy_remove_list) = fill_bids_pseudorandom();
-        let (sell_side, sell_remove_list) = fill_bids_pseudorandom();
-        let order_book = OrderBook {
-            bids: buy_side,
-            asks: sell_side,
-        };
-        remove(order_book, buy_remove_list, sell_remove_list);
+    fn test_last_traded_price() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(5, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::buy(Price::new(4, 0), IdentifiableOrder::new(2, 100)))
+            .unwrap();
+        assert_eq!(order_book.get_last_traded_price(), None);
+
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(3, 50)))
+            .unwrap();
+        assert_eq!(order_book.get_last_traded_price(), Some(&Price::new(5, 0)));
+        assert_eq!(order_book.get_price(), Some(&Price::new(4, 0)));
+
+        order_book.cancel(1).unwrap();
+        assert_eq!(order_book.get_last_traded_price(), Some(&Price::new(5, 0)));
     }
 
-    fn remove(
-        mut order_book: OrderBook,
-        buy_remove_list: Vec<Order>,
-        sell_remove_list: Vec<Order>,
-    ) {
-        debug!(
-            "Before Remove Bid Orderbook length {}",
-            order_book.bids.order_list.len()
+    /* Instrument Rules Tests */
+
+    fn quarter_tick_order_book() -> OrderBook {
+        OrderBook::with_rules(InstrumentRules::new(2, Price::new(0, 25)).unwrap())
+    }
+
+    /// Off-tick prices are rejected on every entry point and leave the orderbook unchanged
+    #[test]
+    fn test_off_tick_price_rejected() {
+        let mut order_book = quarter_tick_order_book();
+        let off_tick = || Order::buy(Price::new(10, 10), IdentifiableOrder::new(1, 100));
+        let rejected = BookError::InvalidPrice(PriceError::OffTick);
+
+        assert_eq!(
+            order_book.insert_order(off_tick()).unwrap_err(),
+            rejected.clone()
         );
-        for remove_order in buy_remove_list {
-            order_book.remove_buy_order(remove_order)
-        }
-        debug!(
-            "After Remove Bid Orderbook length {}",
-            order_book.bids.order_list.len()
+        assert_eq!(
+            order_book
+                .limit_or_cancel_insert(off_tick(), PostOnlyMode::Reject)
+                .unwrap_err(),
+            rejected.clone()
         );
-        debug!(
-            "Before Remove Ask Orderbook length {}",
-            order_book.asks.order_list.len()
+        assert_eq!(
+            order_book.match_and_insert(off_tick()).unwrap_err(),
+            rejected.clone()
         );
-        for remove_order in sell_remove_list {
-            order_book.remove_sell_order(remove_order)
-        }
-        debug!(
-            "After Remove Ask Orderbook length {}",
-            order_book.asks.order_list.len()
+        assert_eq!(
+            order_book
+                .immediate_or_cancel_insert(off_tick())
+                .unwrap_err(),
+            rejected.clone()
         );
-        assert_eq!(order_book.bids.order_list.len(), 0);
-        assert_eq!(order_book.asks.order_list.len(), 0);
+        assert_eq!(
+            order_book.fill_or_kill_insert(off_tick()).unwrap_err(),
+            rejected.clone()
+        );
+        assert!(order_book.get_price().is_none());
+
+        // Market orders are not limited by their price
+        assert!(order_book.market(off_tick()).is_ok());
+
+        order_book
+            .insert_order(Order::buy(
+                Price::new(10, 25),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        assert_eq!(
+            order_book.amend(2, 100, Price::new(10, 30)).unwrap_err(),
+            rejected
+        );
+        assert_eq!(order_book.get_price(), Some(&Price::new(10, 25)));
     }
 
+    /// Post only orders slide by the tick size of the instrument
     #[test]
-    fn test_insert_match_remove() {
-        let (bids, buy_remove_list) = fill_bids_pseudorandom();
-        let (asks, sell_remove_list) = fill_bids_pseudorandom();
-        let mut order_book = OrderBook { bids, asks };
-        // Put in equivalent buy limit orders now as sell market orders
-        // Empties the orderbook completely (qty of all sell market orders == qty of all buy limit orders)
-        for order in buy_remove_list {
-            order_book.market_sell(order);
-        }
+    fn test_post_only_repriced_by_tick_size() {
+        let mut order_book = quarter_tick_order_book();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        let (result, _) = order_book
+            .limit_or_cancel_insert(
+                Order::buy(Price::new(10, 50), IdentifiableOrder::new(2, 100)),
+                PostOnlyMode::Slide,
+            )
+            .unwrap();
+        assert_eq!(result, PostOnlyOutcome::Repriced(Price::new(9, 75)));
+    }
 
-        // Put in equivalent sell limit orders now as buy market orders
-        // Should empty the orderbook completely (qty of all buy market orders == qty of all sell limit orders)
-        for order in sell_remove_list {
-            order_book.market_buy(order);
-        }
-        assert_eq!(order_book.bids.order_list.len(), 0);
-        assert_eq!(order_book.asks.order_list.len(), 0);
+    /// Prices with eight decimal places are matched without rounding
+    #[test]
+    fn test_eight_decimal_places() {
+        let rules = InstrumentRules::new(8, Price::from_units(1, 8).unwrap()).unwrap();
+        let mut order_book = OrderBook::with_rules(rules);
+        let ask = Price::from_units(2_650_012_345_678, 8).unwrap();
+        order_book
+            .insert_order(Order::sell(ask.clone(), IdentifiableOrder::new(1, 100)))
+            .unwrap();
+
+        // One satoshi below the ask does not match
+        let report = order_book
+            .immediate_or_cancel_insert(Order::buy(
+                ask.prev_tick().unwrap(),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        assert!(report.get_fills().is_empty());
+
+        // Prices with less decimal places are widened to the scale of the instrument
+        let report = order_book
+            .immediate_or_cancel_insert(Order::buy(
+                Price::new(26_501, 0),
+                IdentifiableOrder::new(3, 100),
+            ))
+            .unwrap();
+        assert_eq!(report.get_fills()[0].get_price(), &ask);
+        assert_eq!(
+            order_book.insert_order(Order::buy(
+                Price::from_units(1, 9).unwrap(),
+                IdentifiableOrder::new(4, 100)
+            )),
+            Err(BookError::InvalidPrice(PriceError::TooPrecise { scale: 8 }))
+        );
     }
 
+    /// Quantity rules are enforced on every entry point
     #[test]
-    fn test_insert_random_limit_orders_remove() {
-        let (bids, _) = fill_bids_pseudorandom();
-        let orders = generate_orders_pseudorandom(1000);
-        let mut order_book = OrderBook::new(bids, OrderList::default());
+    fn test_quantity_rules() {
+        let rules = InstrumentRules::default()
+            .with_lot_size(10)
+            .and_then(|rules| rules.with_min_qty(20))
+            .and_then(|rules| rules.with_max_qty(1000))
+            .unwrap();
+        let mut order_book = OrderBook::with_rules(rules);
+        let order = |qty| Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, qty));
 
-        for order in orders {
-            order_book.match_and_insert(order, OrderType::Sell);
-        }
-        println!("{}", order_book);
+        assert_eq!(
+            order_book.insert_order(order(25)).unwrap_err(),
+            BookError::OffLot {
+                qty: 25,
+                lot_size: 10
+            }
+        );
+        assert_eq!(
+            order_book.market(order(10)).unwrap_err(),
+            BookError::BelowMinQuantity {
+                qty: 10,
+                min_qty: 20
+            }
+        );
+        assert_eq!(
+            order_book.fill_or_kill_insert(order(1010)).unwrap_err(),
+            BookError::AboveMaxQuantity {
+                qty: 1010,
+                max_qty: 1000
+            }
+        );
+        assert_eq!(
+            order_book
+                .limit_or_cancel_insert(order(0), PostOnlyMode::Reject)
+                .unwrap_err(),
+            BookError::ZeroQuantity
+        );
+        assert!(order_book.get_price().is_none());
+
+        order_book.match_and_insert(order(1000)).unwrap();
+        assert_eq!(
+            order_book.amend(1, 15, Price::new(10, 0)).unwrap_err(),
+            BookError::OffLot {
+                qty: 15,
+                lot_size: 10
+            }
+        );
+        assert!(order_book.amend(1, 20, Price::new(10, 0)).is_ok());
     }
 
-    /*
-        Market Buy Tests
-    */
+    /// Limit orders are checked at their limit price, market orders at the price of their fills
+    #[test]
+    fn test_max_notional() {
+        let rules = InstrumentRules::default()
+            .with_max_notional(Price::new(1000, 0).checked_mul_qty(1).unwrap());
+        let mut order_book = OrderBook::with_rules(rules);
 
-    /// Market Buy Test with randomly filled orderbook
+        assert_eq!(
+            order_book
+                .match_and_insert(Order::sell(
+                    Price::new(10, 1),
+                    IdentifiableOrder::new(1, 100)
+                ))
+                .unwrap_err(),
+            BookError::AboveMaxNotional {
+                notional: Notional::from_units(100100, 2),
+                max_notional: Notional::from_units(1000, 0),
+            }
+        );
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 50),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(20, 0),
+                IdentifiableOrder::new(2, 50),
+            ))
+            .unwrap();
+
+        // 50 * 10.00 + 26 * 20.00 = 1020.00
+        assert_eq!(
+            order_book
+                .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(3, 76)))
+                .unwrap_err(),
+            BookError::AboveMaxNotional {
+                notional: Notional::from_units(102000, 2),
+                max_notional: Notional::from_units(1000, 0),
+            }
+        );
+        // 50 * 10.00 + 25 * 20.00 = 1000.00
+        let report = order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(3, 75)))
+            .unwrap();
+        assert!(report.is_filled());
+    }
+
+    /// A post only order sliding behind the best bid is checked at its new price
     #[test]
-    fn test_market_buy_random() {
-        let (sell_side, _) = fill_asks_pseudorandom();
-        let mut order_book = OrderBook::new(OrderList::default(), sell_side);
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_buy(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 512, 512));
+    fn test_max_notional_post_only_repriced() {
+        let rules = InstrumentRules::default()
+            .with_max_notional(Price::new(1000, 0).checked_mul_qty(1).unwrap());
+        let mut order_book = OrderBook::with_rules(rules);
+        order_book
+            .insert_order(Order::buy(Price::new(20, 0), IdentifiableOrder::new(1, 1)))
+            .unwrap();
+
+        // 999 * 1.00 is within the limit, 999 * 20.01 is not
+        let (outcome, report) = order_book
+            .limit_or_cancel_insert(
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(2, 999)),
+                PostOnlyMode::Slide,
+            )
+            .unwrap();
+        assert_eq!(outcome, PostOnlyOutcome::Rejected);
+        assert_eq!(report.get_cancelled_qty(), 999);
+        assert_eq!(order_book.lowest_ask(), None);
+
+        let (outcome, _) = order_book
+            .limit_or_cancel_insert(
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(3, 49)),
+                PostOnlyMode::Slide,
+            )
+            .unwrap();
+        assert_eq!(outcome, PostOnlyOutcome::Repriced(Price::new(20, 1)));
     }
 
-    /// Normal Market Buy Test.
+    /* Trade Tape Tests */
+
+    /// Every fill is recorded on the trade tape with its aggressor side and the time of the match
     #[test]
-    fn test_market_buy_normal() {
+    fn test_trade_tape() {
+        let clock = ManualClock::new(1_000);
         let mut order_book = OrderBook::default();
-        // Fill orderbook
-        for i in 1..=10 {
-            let price = Price::new(i, 0);
-            let qty = 100;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            order_book.insert_sell_order(order);
+        order_book.set_clock(clock.clone());
+        for (order_id, price) in [(1, 10), (2, 11)] {
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
         }
+        order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 150),
+            ))
+            .unwrap();
+        clock.advance(500);
+        order_book
+            .insert_order(Order::buy(Price::new(9, 0), IdentifiableOrder::new(3, 100)))
+            .unwrap();
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(11, 40),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            order_book.get_trade_tape().iter().collect::<Vec<_>>(),
+            vec![
+                &Trade::new(1, Price::new(10, 0), 100, OrderType::Buy, 1, 10, 1_000),
+                &Trade::new(2, Price::new(11, 0), 50, OrderType::Buy, 2, 10, 1_000),
+                &Trade::new(3, Price::new(9, 0), 40, OrderType::Sell, 3, 11, 1_500),
+            ]
+        );
 
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_buy(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 512, 512));
+        // Only the most recent trades are kept, trade ids keep counting
+        order_book.set_trade_tape_capacity(2);
+        assert_eq!(order_book.get_trade_tape().len(), 2);
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(12, 10),
+            ))
+            .unwrap();
+        let trade_ids: Vec<u64> = order_book
+            .get_trade_tape()
+            .iter()
+            .map(Trade::get_trade_id)
+            .collect();
+        assert_eq!(trade_ids, vec![3, 4]);
     }
 
-    /// Market Buy Full Fill Test
+    /// Session statistics cover all trades, including the ones dropped from the trade tape
     #[test]
-    fn test_market_buy_full_fill() {
+    fn test_session_stats() {
         let mut order_book = OrderBook::default();
-        // Fill orderbook
-        for i in 1..=5 {
-            let price = Price::new(i, 0);
-            let qty = 100;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            order_book.insert_sell_order(order);
+        order_book.set_trade_tape_capacity(1);
+        assert_eq!(order_book.get_session_stats().vwap(), None);
+        for (order_id, price) in [(1, 10), (2, 12)] {
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
         }
+        order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 200),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::buy(Price::new(8, 0), IdentifiableOrder::new(3, 100)))
+            .unwrap();
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(11, 200),
+            ))
+            .unwrap();
+
+        let stats = order_book.get_session_stats();
+        assert_eq!(stats.get_last_price(), Some(&Price::new(8, 0)));
+        assert_eq!(stats.get_high(), Some(&Price::new(12, 0)));
+        assert_eq!(stats.get_low(), Some(&Price::new(8, 0)));
+        assert_eq!(stats.get_volume(), 300);
+        assert_eq!(stats.get_trade_count(), 3);
+        // (1000 + 1200 + 800) / 300
+        assert_eq!(stats.get_notional(), Some(Notional::from_units(300_000, 2)));
+        assert_eq!(stats.vwap(), Some(10.0));
 
-        let identifiable_order = IdentifiableOrder::new(5, 500);
-        let result = order_book.market_buy(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 500, 500));
+        order_book.reset_session_stats();
+        assert_eq!(order_book.get_session_stats(), &SessionStats::default());
+        assert_eq!(order_book.get_trade_tape().len(), 1);
+        assert_eq!(order_book.get_last_traded_price(), Some(&Price::new(8, 0)));
     }
 
-    /// Market Buy more than Orderbook has test
+    /* Candle Tests */
+
+    /// Fills of the orderbook are aggregated into candles of every configured interval
     #[test]
-    fn test_market_buy_exceeding_orderbook() {
+    fn test_candles() {
+        let clock = ManualClock::new(60_000);
         let mut order_book = OrderBook::default();
-        // Fill orderbook
-        for i in 1..=5 {
-            let price = Price::new(i, 0);
-            let qty = 100;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            order_book.insert_sell_order(order);
+        order_book.set_clock(clock.clone());
+        order_book.add_candle_builder(CandleBuilder::new(BarInterval::Time(60_000)).unwrap());
+        order_book.add_candle_builder(CandleBuilder::new(BarInterval::Ticks(2)).unwrap());
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(11, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 150),
+            ))
+            .unwrap();
+        clock.advance(30_000);
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(11, 20)))
+            .unwrap();
+
+        let ticks = order_book
+            .get_candle_builder(BarInterval::Ticks(2))
+            .unwrap();
+        assert_eq!(ticks.get_candles().count(), 1);
+        assert_eq!(ticks.get_current().unwrap().get_volume(), 20);
+
+        let minutes = order_book
+            .get_candle_builder(BarInterval::Time(60_000))
+            .unwrap();
+        assert_eq!(minutes.get_candles().count(), 0);
+        clock.advance(30_000);
+        order_book.close_candles();
+        let minutes = order_book
+            .get_candle_builder(BarInterval::Time(60_000))
+            .unwrap();
+        let candle = minutes.get_candles().next().unwrap();
+        assert_eq!(candle.get_open_time(), 60_000);
+        assert_eq!(candle.get_close_time(), 90_000);
+        assert_eq!(candle.get_open(), &Price::new(10, 0));
+        assert_eq!(candle.get_high(), &Price::new(11, 0));
+        assert_eq!(candle.get_low(), &Price::new(10, 0));
+        assert_eq!(candle.get_close(), &Price::new(11, 0));
+        assert_eq!(candle.get_volume(), 170);
+        assert_eq!(candle.get_trade_count(), 3);
+        assert!(order_book
+            .get_candle_builder(BarInterval::Volume(10))
+            .is_none());
+    }
+
+    /* Stop Order Tests */
+
+    /// Stop orders wait in the trigger book until the last traded price reaches their stop price
+    #[test]
+    fn test_stop_market_triggered() {
+        let mut order_book = fixed_clock_order_book(EventCollector::default());
+        for (order_id, price) in [(1, 10), (2, 11), (3, 12)] {
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
         }
+        let stop = Order::buy(Price::new(1, 0), IdentifiableOrder::new(20, 50))
+            .with_kind(OrderKind::StopMarket)
+            .with_stop_price(Price::new(11, 0));
+        let report = order_book.submit(stop.clone()).unwrap();
+        assert_eq!(report.get_resting(), Some(&stop));
+        assert_eq!(order_book.iter_buy_stops().collect::<Vec<_>>(), vec![&stop]);
+        assert_eq!(
+            order_book.submit(stop.clone()).unwrap_err(),
+            BookError::DuplicateOrderId(20)
+        );
 
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_buy(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 512, 500));
+        // Trades at 10.00 do not reach the stop price
+        order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 100),
+            ))
+            .unwrap();
+        assert_eq!(order_book.iter_buy_stops().count(), 1);
+        order_book.get_listener_mut().take_events();
+
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(11, 60)))
+            .unwrap();
+        assert_eq!(order_book.iter_buy_stops().count(), 0);
+        let events = order_book.get_listener_mut().take_events();
+        assert!(events.contains(&BookEvent::StopTriggered {
+            order_id: 20,
+            order_type: OrderType::Buy,
+            stop_price: Price::new(11, 0),
+        }));
+        let fills: Vec<(u64, u64, u64)> = order_book
+            .get_trade_tape()
+            .iter()
+            .map(|trade| (trade.get_maker_id(), trade.get_taker_id(), trade.get_qty()))
+            .collect();
+        assert_eq!(
+            fills,
+            vec![(1, 10, 100), (2, 11, 60), (2, 20, 40), (3, 20, 10)]
+        );
     }
 
-    /// Orderbook Empty Market Buy Test
+    /// Stops triggered by other stop orders are executed within the same call
     #[test]
-    fn test_market_buy_empty_book() {
+    fn test_stop_cascade() {
+        let mut order_book = OrderBook::default();
+        for (order_id, price) in [(1, 10), (2, 9), (3, 8), (4, 7)] {
+            order_book
+                .insert_order(Order::buy(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
+        }
+        for (order_id, stop_price) in [(21, 8), (20, 9)] {
+            order_book
+                .submit(
+                    Order::sell(Price::new(1, 0), IdentifiableOrder::new(order_id, 100))
+                        .with_kind(OrderKind::StopMarket)
+                        .with_stop_price(Price::new(stop_price, 0)),
+                )
+                .unwrap();
+        }
+        let trigger_order: Vec<u64> = order_book
+            .iter_sell_stops()
+            .map(|order| order.get_order().get_id())
+            .collect();
+        assert_eq!(trigger_order, vec![20, 21]);
+
+        // Hits 9.00, the stop at 9.00 hits 8.00, which triggers the stop at 8.00
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 150),
+            ))
+            .unwrap();
+        let fills: Vec<(u64, u64, u64)> = order_book
+            .get_trade_tape()
+            .iter()
+            .map(|trade| (trade.get_maker_id(), trade.get_taker_id(), trade.get_qty()))
+            .collect();
+        assert_eq!(
+            fills,
+            vec![
+                (1, 10, 100),
+                (2, 10, 50),
+                (2, 20, 50),
+                (3, 20, 50),
+                (3, 21, 50),
+                (4, 21, 50)
+            ]
+        );
+        assert_eq!(order_book.iter_sell_stops().count(), 0);
+        assert_eq!(order_book.get_last_traded_price(), Some(&Price::new(7, 0)));
+    }
+
+    /// Stop limit orders past their stop price are executed immediately, waiting stops can be cancelled
+    #[test]
+    fn test_stop_limit() {
+        let mut order_book = fixed_clock_order_book(NoopListener);
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        assert_eq!(
+            order_book
+                .submit(
+                    Order::buy(Price::new(10, 0), IdentifiableOrder::new(20, 10))
+                        .with_kind(OrderKind::StopLimit)
+                )
+                .unwrap_err(),
+            BookError::InvalidStopOrder
+        );
+        assert_eq!(
+            order_book
+                .stop_insert(
+                    Order::buy(Price::new(10, 0), IdentifiableOrder::new(20, 10))
+                        .with_stop_price(Price::new(10, 0))
+                )
+                .unwrap_err(),
+            BookError::InvalidStopOrder
+        );
+
+        let stop = Order::sell(Price::new(9, 50), IdentifiableOrder::new(21, 10))
+            .with_kind(OrderKind::StopLimit)
+            .with_stop_price(Price::new(9, 0));
+        order_book.submit(stop.clone()).unwrap();
+        let cancelled = order_book.cancel(21).unwrap();
+        assert_eq!(cancelled.get_order(), &stop);
+        assert_eq!(order_book.iter_sell_stops().count(), 0);
+
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 10)))
+            .unwrap();
+        // Last traded price of 10.00 is already above the stop price
+        let report = order_book
+            .submit(
+                Order::buy(Price::new(10, 50), IdentifiableOrder::new(22, 100))
+                    .with_kind(OrderKind::StopLimit)
+                    .with_stop_price(Price::new(10, 0)),
+            )
+            .unwrap();
+        assert_eq!(report.get_filled_qty(), 90);
+        assert_eq!(report.get_resting_qty(), 10);
+        assert_eq!(
+            order_book.best_bid().unwrap().get_price(),
+            &Price::new(10, 50)
+        );
+    }
+
+    /* Iceberg Order Tests */
+
+    /// Only the display quantity of an iceberg order is visible in level-2 and level-3 views
+    #[test]
+    fn test_iceberg_display() {
         let mut order_book = OrderBook::default();
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_buy(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (false, 512, 0));
+        let report = order_book
+            .match_and_insert(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100).with_display_qty(30),
+            ))
+            .unwrap();
+        assert_eq!(report.get_resting_qty(), 100);
+        assert_eq!(
+            order_book.depth(1).get_asks(),
+            &[PriceLevel::new(Price::new(10, 0), 30, 1)]
+        );
+        assert_eq!(order_book.iter_asks().next().unwrap().get_qty(), 30);
+
+        // Hidden reserve counts as liquidity
+        let report = order_book
+            .fill_or_kill_insert(Order::buy(
+                Price::new(10, 0),
+                IdentifiableOrder::new(10, 80),
+            ))
+            .unwrap();
+        assert!(report.is_filled());
+        // Third peak of 30 has been filled by 20
+        assert_eq!(
+            order_book.depth(1).get_asks(),
+            &[PriceLevel::new(Price::new(10, 0), 10, 1)]
+        );
+        assert_eq!(
+            order_book
+                .match_and_insert(Order::sell(
+                    Price::new(10, 0),
+                    IdentifiableOrder::new(2, 100).with_display_qty(0),
+                ))
+                .map(|report| report.get_resting_qty()),
+            Err(BookError::InvalidDisplayQuantity {
+                display_qty: 0,
+                qty: 100
+            })
+        );
+        assert_eq!(
+            order_book
+                .insert_order(Order::sell(
+                    Price::new(10, 0),
+                    IdentifiableOrder::new(2, 100).with_display_qty(101),
+                ))
+                .unwrap_err(),
+            BookError::InvalidDisplayQuantity {
+                display_qty: 101,
+                qty: 100
+            }
+        );
+        assert_eq!(
+            order_book.depth(1).get_asks(),
+            &[PriceLevel::new(Price::new(10, 0), 10, 1)]
+        );
     }
 
-    /*
-        Market Sell Tests
-    */
+    /// Events report the display quantity, the hidden reserve only in its own field
+    #[test]
+    fn test_iceberg_events() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book
+            .match_and_insert(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100).with_display_qty(30),
+            ))
+            .unwrap();
+        let events = order_book.get_listener_mut().take_events();
+        assert!(events.contains(&BookEvent::OrderAccepted {
+            order_id: 1,
+            order_type: OrderType::Sell,
+            price: Price::new(10, 0),
+            qty: 30,
+            hidden_qty: 70,
+        }));
+        assert!(events.contains(&BookEvent::OrderRested {
+            order_id: 1,
+            order_type: OrderType::Sell,
+            price: Price::new(10, 0),
+            qty: 30,
+            hidden_qty: 70,
+        }));
 
-    /// Market Sell Test with randomly filled orderbook
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 10)))
+            .unwrap();
+        let events = order_book.get_listener_mut().take_events();
+        assert!(events.contains(&BookEvent::OrderPartiallyFilled {
+            order_id: 1,
+            order_type: OrderType::Sell,
+            counterparty_id: 10,
+            price: Price::new(10, 0),
+            qty: 10,
+            remaining_qty: 20,
+            hidden_qty: 70,
+        }));
+
+        order_book.amend(1, 50, Price::new(10, 0)).unwrap();
+        let events = order_book.get_listener_mut().take_events();
+        assert!(events.contains(&BookEvent::OrderAmended {
+            order_id: 1,
+            order_type: OrderType::Sell,
+            price: Price::new(10, 0),
+            qty: 20,
+            hidden_qty: 30,
+        }));
+
+        order_book.cancel(1).unwrap();
+        let events = order_book.get_listener_mut().take_events();
+        assert!(events.contains(&BookEvent::OrderCancelled {
+            order_id: 1,
+            order_type: OrderType::Sell,
+            qty: 20,
+            hidden_qty: 30,
+        }));
+    }
+
+    /// Each replenished peak is queued behind the orders already resting at its price
     #[test]
-    fn test_market_sell_random() {
-        let (buy_side, _) = fill_bids_pseudorandom();
-        let mut order_book = OrderBook::new(buy_side, OrderList::default());
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_sell(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 512, 512));
+    fn test_iceberg_replenish() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100).with_display_qty(30),
+            ))
+            .unwrap();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(2, 50),
+            ))
+            .unwrap();
+        order_book.get_listener_mut().take_events();
+
+        let report = order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 40)))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[
+                Fill::new(1, 10, Price::new(10, 0), 30),
+                Fill::new(2, 10, Price::new(10, 0), 10),
+            ]
+        );
+        let events = order_book.get_listener_mut().take_events();
+        assert!(events.contains(&BookEvent::OrderPartiallyFilled {
+            order_id: 1,
+            order_type: OrderType::Sell,
+            counterparty_id: 10,
+            price: Price::new(10, 0),
+            qty: 30,
+            remaining_qty: 0,
+            hidden_qty: 70,
+        }));
+        assert!(events.contains(&BookEvent::OrderReplenished {
+            order_id: 1,
+            order_type: OrderType::Sell,
+            price: Price::new(10, 0),
+            qty: 30,
+        }));
+        let queue: Vec<(u64, u64, usize)> = order_book
+            .iter_asks()
+            .map(|order| {
+                (
+                    order.get_order_id(),
+                    order.get_qty(),
+                    order.get_queue_position(),
+                )
+            })
+            .collect();
+        assert_eq!(queue, vec![(2, 40, 0), (1, 30, 1)]);
+
+        // Last peak only shows what is left of the reserve
+        order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(11, 100),
+            ))
+            .unwrap();
+        let queue: Vec<(u64, u64)> = order_book
+            .iter_asks()
+            .map(|order| (order.get_order_id(), order.get_qty()))
+            .collect();
+        assert_eq!(queue, vec![(1, 10)]);
+        let cancelled = order_book.cancel(1).unwrap();
+        assert_eq!(cancelled.get_order().get_order().get_total_qty(), 10);
+        assert!(order_book.iter_asks().next().is_none());
     }
 
-    /// Normal Market Sell Test.
+    /// Amending an iceberg order applies to its total quantity and reduces the reserve first
     #[test]
-    fn test_market_sell_normal() {
+    fn test_iceberg_amend() {
         let mut order_book = OrderBook::default();
-        // Fill orderbook
-        for i in 1..=10 {
-            let price = Price::new(i, 0);
-            let qty = 100;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            order_book.insert_buy_order(order);
-        }
+        order_book
+            .insert_order(Order::buy(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100).with_display_qty(30),
+            ))
+            .unwrap();
+        let report = order_book.amend(1, 50, Price::new(10, 0)).unwrap();
+        assert_eq!(report.get_resting_qty(), 50);
+        assert_eq!(order_book.iter_bids().next().unwrap().get_qty(), 30);
+        order_book.amend(1, 20, Price::new(10, 0)).unwrap();
+        assert_eq!(order_book.iter_bids().next().unwrap().get_qty(), 20);
 
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_sell(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 512, 512));
+        // New price keeps the display quantity
+        order_book.amend(1, 90, Price::new(11, 0)).unwrap();
+        assert_eq!(
+            order_book.depth(1).get_bids(),
+            &[PriceLevel::new(Price::new(11, 0), 30, 1)]
+        );
+        let report = order_book.amend(1, 0, Price::new(11, 0)).unwrap();
+        assert_eq!(report.get_cancelled_qty(), 90);
     }
 
-    /// Market Sell Full Fill Test
+    /* Order Group Tests */
+
+    /// Fill of one order of an OCO pair cancels the other order before the taker order can reach it
     #[test]
-    fn test_market_sell_full_fill() {
-        //initialize_tracing();
+    fn test_oco_cancel_on_fill() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        let (first, second) = order_book
+            .one_cancels_other_insert(
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(1, 50)),
+                Order::sell(Price::new(11, 0), IdentifiableOrder::new(2, 50)),
+                OcoTrigger::Fill,
+            )
+            .unwrap();
+        assert_eq!(
+            (first.get_resting_qty(), second.get_resting_qty()),
+            (50, 50)
+        );
+        order_book.get_listener_mut().take_events();
+
+        let report = order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 100),
+            ))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[Fill::new(1, 10, Price::new(10, 0), 50)]
+        );
+        assert_eq!(report.get_cancelled_qty(), 50);
+        assert!(order_book
+            .get_listener()
+            .get_events()
+            .contains(&BookEvent::OrderCancelled {
+                order_id: 2,
+                order_type: OrderType::Sell,
+                qty: 50,
+                hidden_qty: 0,
+            }));
+        assert!(order_book.iter_asks().next().is_none());
+    }
+
+    /// OCO pairs can cancel the other order on any partial fill
+    #[test]
+    fn test_oco_cancel_on_partial_fill() {
         let mut order_book = OrderBook::default();
-        // Fill orderbook
-        for i in 1..=5 {
-            let price = Price::new(i, 0);
-            let qty = 100;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            order_book.insert_buy_order(order);
+        order_book
+            .one_cancels_other_insert(
+                Order::sell(Price::new(12, 0), IdentifiableOrder::new(1, 100)),
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(2, 100))
+                    .with_kind(OrderKind::StopMarket)
+                    .with_stop_price(Price::new(9, 0)),
+                OcoTrigger::PartialFill,
+            )
+            .unwrap();
+        assert_eq!(order_book.iter_sell_stops().count(), 1);
+
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 30)))
+            .unwrap();
+        assert_eq!(order_book.iter_sell_stops().count(), 0);
+        assert_eq!(order_book.iter_asks().next().unwrap().get_qty(), 70);
+
+        // Cancelled order was the only link
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(11, 70)))
+            .unwrap();
+        assert!(order_book.links.is_empty());
+    }
+
+    /// Removing one order of an OCO pair removes the link of the other order
+    #[test]
+    fn test_oco_remove_order() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .one_cancels_other_insert(
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(1, 5)),
+                Order::sell(Price::new(11, 0), IdentifiableOrder::new(2, 5)),
+                OcoTrigger::Fill,
+            )
+            .unwrap();
+        order_book
+            .remove_order(Order::sell(Price::new(10, 0), IdentifiableOrder::new(1, 5)))
+            .unwrap();
+        assert!(order_book.links.is_empty());
+
+        let report = order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 5)))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[Fill::new(2, 10, Price::new(11, 0), 5)]
+        );
+        assert!(order_book.links.is_empty());
+    }
+
+    /// A level emptied by the cancellation of the other OCO order is only updated once
+    #[test]
+    fn test_oco_cancel_same_level() {
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book
+            .one_cancels_other_insert(
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(1, 5)),
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(2, 5)),
+                OcoTrigger::Fill,
+            )
+            .unwrap();
+        let sequence = order_book.get_sequence();
+        order_book.get_listener_mut().take_events();
+
+        order_book
+            .market(Order::buy(Price::new(1, 0), IdentifiableOrder::new(10, 5)))
+            .unwrap();
+        let level_updates: Vec<BookEvent> = order_book
+            .get_listener_mut()
+            .take_events()
+            .into_iter()
+            .filter(|event| matches!(event, BookEvent::LevelUpdated(_)))
+            .collect();
+        assert_eq!(
+            level_updates,
+            [BookEvent::LevelUpdated(LevelUpdate::new(
+                sequence + 1,
+                OrderType::Sell,
+                PriceLevel::new(Price::new(10, 0), 0, 0),
+            ))]
+        );
+        assert_eq!(order_book.get_sequence(), sequence + 1);
+    }
+
+    /// Only one order of an OCO pair counts as liquidity for a fill or kill order
+    #[test]
+    fn test_oco_fill_or_kill() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .one_cancels_other_insert(
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(1, 5)),
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(2, 5)),
+                OcoTrigger::Fill,
+            )
+            .unwrap();
+
+        let report = order_book
+            .fill_or_kill_insert(Order::buy(
+                Price::new(10, 0),
+                IdentifiableOrder::new(10, 10),
+            ))
+            .unwrap();
+        assert!(report.get_fills().is_empty());
+        assert_eq!(report.get_cancelled_qty(), 10);
+        assert_eq!(
+            order_book.depth(1).get_asks(),
+            &[PriceLevel::new(Price::new(10, 0), 10, 2)]
+        );
+
+        let report = order_book
+            .fill_or_kill_insert(Order::buy(Price::new(10, 0), IdentifiableOrder::new(11, 5)))
+            .unwrap();
+        assert!(report.is_filled());
+        assert!(order_book.iter_asks().next().is_none());
+    }
+
+    /// The second order of an OCO pair is not submitted if the first order is executed immediately
+    #[test]
+    fn test_oco_first_executed() {
+        let mut order_book = OrderBook::default();
+        order_book
+            .insert_order(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(1, 100),
+            ))
+            .unwrap();
+        let (first, second) = order_book
+            .one_cancels_other_insert(
+                Order::buy(Price::new(10, 0), IdentifiableOrder::new(2, 100)),
+                Order::buy(Price::new(9, 0), IdentifiableOrder::new(3, 100)),
+                OcoTrigger::Fill,
+            )
+            .unwrap();
+        assert!(first.is_filled());
+        assert_eq!(second.get_cancelled_qty(), 100);
+        assert!(order_book.iter_bids().next().is_none());
+        assert!(order_book.links.is_empty());
+
+        assert_eq!(
+            order_book
+                .one_cancels_other_insert(
+                    Order::buy(Price::new(10, 0), IdentifiableOrder::new(4, 100)),
+                    Order::buy(Price::new(9, 0), IdentifiableOrder::new(4, 100)),
+                    OcoTrigger::Fill,
+                )
+                .unwrap_err(),
+            BookError::InvalidOrderGroup
+        );
+        // Invalid second order rejects the pair before anything is submitted
+        assert_eq!(
+            order_book
+                .one_cancels_other_insert(
+                    Order::buy(Price::new(10, 0), IdentifiableOrder::new(4, 100)),
+                    Order::buy(
+                        Price::from_units(9001, 3).unwrap(),
+                        IdentifiableOrder::new(5, 100)
+                    ),
+                    OcoTrigger::Fill,
+                )
+                .unwrap_err(),
+            BookError::InvalidPrice(PriceError::TooPrecise { scale: 2 })
+        );
+        assert!(order_book.iter_bids().next().is_none());
+    }
+
+    /// A rejected market order cancels its pair instead of leaving the first order open on its own
+    #[test]
+    fn test_oco_market_notional() {
+        let rules = InstrumentRules::default()
+            .with_max_notional(Price::new(1000, 0).checked_mul_qty(1).unwrap());
+        let mut order_book = OrderBook::with_rules(rules);
+        order_book
+            .insert_order(Order::buy(Price::new(20, 0), IdentifiableOrder::new(1, 45)))
+            .unwrap();
+
+        // 45 * 20.00 is within the limit, 55 * 20.00 once the first order rests is not
+        let (first, second) = order_book
+            .one_cancels_other_insert(
+                Order::buy(Price::new(20, 0), IdentifiableOrder::new(2, 10)),
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(3, 55))
+                    .with_kind(OrderKind::Market),
+                OcoTrigger::Fill,
+            )
+            .unwrap();
+        assert_eq!(
+            (first.get_resting_qty(), first.get_cancelled_qty()),
+            (0, 10)
+        );
+        assert_eq!(second.get_cancelled_qty(), 55);
+        assert_eq!(
+            order_book.depth(2).get_bids(),
+            &[PriceLevel::new(Price::new(20, 0), 45, 1)]
+        );
+
+        // 45 * 20.00 + 11 * 10.00 = 1010.00 is rejected before the first order is submitted
+        order_book
+            .insert_order(Order::buy(Price::new(10, 0), IdentifiableOrder::new(4, 11)))
+            .unwrap();
+        assert!(matches!(
+            order_book.one_cancels_other_insert(
+                Order::buy(Price::new(1, 0), IdentifiableOrder::new(5, 1)),
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(6, 80))
+                    .with_kind(OrderKind::Market),
+                OcoTrigger::Fill,
+            ),
+            Err(BookError::AboveMaxNotional { .. })
+        ));
+        assert_eq!(
+            order_book.depth(3).get_bids(),
+            &[
+                PriceLevel::new(Price::new(20, 0), 45, 1),
+                PriceLevel::new(Price::new(10, 0), 11, 1)
+            ]
+        );
+        assert!(order_book.links.is_empty());
+    }
+
+    /// Exit orders of a bracket are only submitted once the entry order is filled
+    #[test]
+    fn test_bracket_order() {
+        let mut order_book = OrderBook::default();
+        let report = order_book
+            .bracket_insert(
+                Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, 100)),
+                Order::sell(Price::new(12, 0), IdentifiableOrder::new(2, 100)),
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(3, 100))
+                    .with_kind(OrderKind::StopMarket)
+                    .with_stop_price(Price::new(9, 0)),
+            )
+            .unwrap();
+        assert_eq!(report.get_resting_qty(), 100);
+        assert!(order_book.iter_asks().next().is_none());
+
+        // Partial fill of the entry does not activate the exit orders
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 40),
+            ))
+            .unwrap();
+        assert!(order_book.iter_asks().next().is_none());
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(11, 60),
+            ))
+            .unwrap();
+        assert_eq!(order_book.iter_asks().next().unwrap().get_order_id(), 2);
+        assert_eq!(order_book.iter_sell_stops().count(), 1);
+
+        // Stop loss is triggered at 9.00 and cancels the take profit order
+        for (order_id, price) in [(4, 9), (5, 8)] {
+            order_book
+                .insert_order(Order::buy(
+                    Price::new(price, 0),
+                    IdentifiableOrder::new(order_id, 100),
+                ))
+                .unwrap();
         }
-        debug!("Created Orderbook: {:?}", order_book);
-        let identifiable_order = IdentifiableOrder::new(5, 500);
-        let result = order_book.market_sell(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 500, 500));
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(12, 50),
+            ))
+            .unwrap();
+        assert_eq!(order_book.iter_sell_stops().count(), 0);
+        assert!(order_book.iter_asks().next().is_none());
+        assert_eq!(order_book.get_last_traded_price(), Some(&Price::new(8, 0)));
+        assert!(order_book.links.is_empty());
     }
 
-    /// Market Buy more than Orderbook has test
+    /// Cancelling a bracket entry discards its exit orders, exits have to be on the opposite side
     #[test]
-    fn test_market_sell_exceeding_orderbook() {
+    fn test_bracket_cancel_and_invalid() {
         let mut order_book = OrderBook::default();
-        // Fill orderbook
-        for i in 1..=5 {
-            let price = Price::new(i, 0);
-            let qty = 100;
-            let identifiable_order = IdentifiableOrder::new(1, qty);
-            let order = Order::new(price.into(), identifiable_order);
-            order_book.insert_buy_order(order);
+        assert_eq!(
+            order_book
+                .bracket_insert(
+                    Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, 100)),
+                    Order::buy(Price::new(12, 0), IdentifiableOrder::new(2, 100)),
+                    Order::sell(Price::new(9, 0), IdentifiableOrder::new(3, 100)),
+                )
+                .unwrap_err(),
+            BookError::InvalidOrderGroup
+        );
+        order_book
+            .bracket_insert(
+                Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, 100)),
+                Order::sell(Price::new(12, 0), IdentifiableOrder::new(2, 100)),
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(3, 100))
+                    .with_kind(OrderKind::StopMarket)
+                    .with_stop_price(Price::new(9, 0)),
+            )
+            .unwrap();
+        order_book.cancel(1).unwrap();
+        assert!(order_book.links.is_empty());
+        order_book
+            .insert_order(Order::buy(
+                Price::new(10, 0),
+                IdentifiableOrder::new(4, 100),
+            ))
+            .unwrap();
+        order_book
+            .market(Order::sell(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 100),
+            ))
+            .unwrap();
+        assert!(order_book.iter_asks().next().is_none());
+        assert_eq!(order_book.iter_sell_stops().count(), 0);
+    }
+
+    /* Expiry Tests */
+
+    /// Good till date orders are cancelled by the sweep once their expiry time is reached
+    #[test]
+    fn test_good_till_date_expiry() {
+        let clock = ManualClock::new(1_000);
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book.set_clock(clock.clone());
+        assert_eq!(
+            order_book
+                .submit(
+                    Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, 100))
+                        .with_time_in_force(TimeInForce::GoodTillDate(1_000)),
+                )
+                .unwrap_err(),
+            BookError::ExpiredOrder
+        );
+        let report = order_book
+            .submit(
+                Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, 100))
+                    .with_time_in_force(TimeInForce::GoodTillDate(5_000)),
+            )
+            .unwrap();
+        let resting = report.get_resting().unwrap().get_order();
+        assert_eq!(resting.get_timestamp(), 1_000);
+        assert_eq!(resting.get_expires_at(), Some(5_000));
+        // Filled before its expiry, the sweep skips it
+        order_book
+            .submit(
+                Order::sell(Price::new(11, 0), IdentifiableOrder::new(2, 100))
+                    .with_time_in_force(TimeInForce::GoodTillDate(3_000)),
+            )
+            .unwrap();
+        order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(10, 100),
+            ))
+            .unwrap();
+        order_book
+            .submit(
+                Order::sell(Price::new(1, 0), IdentifiableOrder::new(3, 100))
+                    .with_kind(OrderKind::StopMarket)
+                    .with_stop_price(Price::new(5, 0))
+                    .with_time_in_force(TimeInForce::GoodTillDate(5_000)),
+            )
+            .unwrap();
+        order_book.get_listener_mut().take_events();
+
+        assert!(order_book.expire_until(4_999).is_empty());
+        clock.set(5_000);
+        let expired: Vec<u64> = order_book
+            .expire_until(5_000)
+            .iter()
+            .map(|cancelled| cancelled.get_order().get_order().get_id())
+            .collect();
+        assert_eq!(expired, vec![1, 3]);
+        assert!(order_book.iter_bids().next().is_none());
+        assert_eq!(order_book.iter_sell_stops().count(), 0);
+        assert_eq!(
+            order_book.get_listener().get_events()[0],
+            BookEvent::OrderCancelled {
+                order_id: 1,
+                order_type: OrderType::Buy,
+                qty: 100,
+                hidden_qty: 0,
+            }
+        );
+    }
+
+    /// Day orders expire at the end of the session, amended orders keep their expiry time
+    #[test]
+    fn test_day_expiry() {
+        const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+        let clock = ManualClock::new(NANOS_PER_DAY + 500);
+        let mut order_book = OrderBook::default();
+        order_book.set_clock(clock.clone());
+        assert_eq!(order_book.get_session_end(), 2 * NANOS_PER_DAY);
+
+        order_book.set_session_end(10_000 + NANOS_PER_DAY);
+        for order_id in 1..=2 {
+            order_book
+                .submit(
+                    Order::sell(Price::new(10, 0), IdentifiableOrder::new(order_id, 100))
+                        .with_time_in_force(TimeInForce::Day),
+                )
+                .unwrap();
         }
+        order_book
+            .submit(Order::sell(
+                Price::new(10, 0),
+                IdentifiableOrder::new(3, 100),
+            ))
+            .unwrap();
+        order_book.amend(2, 100, Price::new(11, 0)).unwrap();
 
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_sell(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (true, 512, 500));
+        let expired = order_book.expire_until(10_000 + NANOS_PER_DAY);
+        assert_eq!(expired.len(), 2);
+        let remaining: Vec<u64> = order_book
+            .iter_asks()
+            .map(|order| order.get_order_id())
+            .collect();
+        assert_eq!(remaining, vec![3]);
     }
 
-    /// Orderbook Empty Market Buy Test
+    /// Day orders are rejected after the end of the session
     #[test]
-    fn test_market_sell_empty_book() {
+    fn test_day_after_session_end() {
+        let clock = ManualClock::new(1_000);
         let mut order_book = OrderBook::default();
-        let identifiable_order = IdentifiableOrder::new(5, 512);
-        let result = order_book.market_sell(Order::new(Price::new(1, 0), identifiable_order));
-        assert_eq!(result, (false, 512, 0));
+        order_book.set_clock(clock.clone());
+        order_book.set_session_end(1_000);
+        assert_eq!(
+            order_book
+                .submit(
+                    Order::buy(Price::new(10, 0), IdentifiableOrder::new(1, 100))
+                        .with_time_in_force(TimeInForce::Day),
+                )
+                .unwrap_err(),
+            BookError::ExpiredOrder
+        );
+        assert!(order_book.iter_bids().next().is_none());
+    }
+
+    /// Expired orders are cancelled before an incoming order can be matched against them
+    #[test]
+    fn test_expired_maker_not_matched() {
+        let clock = ManualClock::new(1_000);
+        let mut order_book = OrderBook::with_listener(EventCollector::default());
+        order_book.set_clock(clock.clone());
+        order_book
+            .submit(
+                Order::sell(Price::new(10, 0), IdentifiableOrder::new(1, 100))
+                    .with_time_in_force(TimeInForce::GoodTillDate(2_000)),
+            )
+            .unwrap();
+        order_book
+            .submit(Order::sell(
+                Price::new(11, 0),
+                IdentifiableOrder::new(2, 100),
+            ))
+            .unwrap();
+        clock.set(2_000);
+
+        let report = order_book
+            .fill_or_kill_insert(Order::buy(
+                Price::new(10, 0),
+                IdentifiableOrder::new(10, 100),
+            ))
+            .unwrap();
+        assert_eq!(report.get_cancelled_qty(), 100);
+        assert!(order_book
+            .get_listener()
+            .get_events()
+            .contains(&BookEvent::OrderCancelled {
+                order_id: 1,
+                order_type: OrderType::Sell,
+                qty: 100,
+                hidden_qty: 0,
+            }));
+
+        let report = order_book
+            .market(Order::buy(
+                Price::new(1, 0),
+                IdentifiableOrder::new(11, 100),
+            ))
+            .unwrap();
+        assert_eq!(
+            report.get_fills(),
+            &[Fill::new(2, 11, Price::new(11, 0), 100)]
+        );
+        assert!(order_book.expire_until(2_000).is_empty());
     }
 }
@@ -0,0 +1,109 @@
+use super::order_queue::OrderQueue;
+use crate::{price::Price, traits::matching_engine::OrderType};
+
+/// Aggregated price level of one side of the orderbook
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PriceLevel {
+    price: Price,
+    // Sum of the open quantity of all orders at this price
+    qty: u64,
+    order_count: usize,
+}
+
+impl PriceLevel {
+    pub fn new(price: Price, qty: u64, order_count: usize) -> Self {
+        Self {
+            price,
+            qty,
+            order_count,
+        }
+    }
+
+    /// Aggregates the FIFO queue of a price level
+    pub(crate) fn aggregate((price, orders): (&Price, &OrderQueue)) -> Self {
+        Self::new(price.clone(), orders.get_qty(), orders.len())
+    }
+
+    pub fn get_price(&self) -> &Price {
+        &self.price
+    }
+
+    pub fn get_qty(&self) -> u64 {
+        self.qty
+    }
+
+    pub fn get_order_count(&self) -> usize {
+        self.order_count
+    }
+}
+
+/// Level-2 view of the orderbook, both sides are ordered best price first
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Depth {
+    // Sequence number of the last level update included in this view
+    sequence: u64,
+    // Highest bid first
+    bids: Vec<PriceLevel>,
+    // Lowest ask first
+    asks: Vec<PriceLevel>,
+}
+
+impl Depth {
+    pub fn new(sequence: u64, bids: Vec<PriceLevel>, asks: Vec<PriceLevel>) -> Self {
+        Self {
+            sequence,
+            bids,
+            asks,
+        }
+    }
+
+    /// Level updates up to and including this sequence number are already part of the view
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn get_bids(&self) -> &[PriceLevel] {
+        &self.bids
+    }
+
+    pub fn get_asks(&self) -> &[PriceLevel] {
+        &self.asks
+    }
+}
+
+/// Incremental level-2 update: the price level on the given side now has the given quantity.
+///
+/// A quantity of zero means the price level has been removed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LevelUpdate {
+    // Monotonically increasing per orderbook, starts at 1
+    sequence: u64,
+    order_type: OrderType,
+    level: PriceLevel,
+}
+
+impl LevelUpdate {
+    pub fn new(sequence: u64, order_type: OrderType, level: PriceLevel) -> Self {
+        Self {
+            sequence,
+            order_type,
+            level,
+        }
+    }
+
+    pub fn get_sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn get_order_type(&self) -> OrderType {
+        self.order_type
+    }
+
+    pub fn get_level(&self) -> &PriceLevel {
+        &self.level
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.level.get_qty() == 0
+    }
+}
@@ -0,0 +1,136 @@
+use core::fmt;
+
+use super::orders::Order;
+use crate::price::Price;
+
+/// A single execution between a resting maker order and an incoming taker order.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Fill {
+    maker_id: u64,
+    taker_id: u64,
+    // Fills are always executed at the price of the maker order
+    price: Price,
+    qty: u64,
+}
+
+impl Fill {
+    pub fn new(maker_id: u64, taker_id: u64, price: Price, qty: u64) -> Self {
+        Self {
+            maker_id,
+            taker_id,
+            price,
+            qty,
+        }
+    }
+
+    pub fn get_maker_id(&self) -> u64 {
+        self.maker_id
+    }
+
+    pub fn get_taker_id(&self) -> u64 {
+        self.taker_id
+    }
+
+    pub fn get_price(&self) -> &Price {
+        &self.price
+    }
+
+    pub fn get_qty(&self) -> u64 {
+        self.qty
+    }
+}
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Maker id: {}, Taker id: {}, Price: {}, Quantity: {}",
+            self.maker_id, self.taker_id, self.price, self.qty
+        )
+    }
+}
+
+/// Result of an incoming order passed to the [MatchingEngine](crate::traits::matching_engine::MatchingEngine).
+///
+/// Lists every fill in execution order and what happened to the unfilled remainder.
+/// The remainder is either resting in the orderbook or cancelled, so that `filled + resting + cancelled == qty` always holds.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExecutionReport {
+    taker_id: u64,
+    // Quantity of the incoming order
+    qty: u64,
+    fills: Vec<Fill>,
+    // Remainder which has been inserted into the orderbook
+    resting: Option<Order>,
+    cancelled_qty: u64,
+}
+
+impl ExecutionReport {
+    pub(crate) fn new(taker_order: &Order) -> Self {
+        Self {
+            taker_id: taker_order.get_order().get_id(),
+            qty: taker_order.get_order().get_total_qty(),
+            fills: vec![],
+            resting: None,
+            cancelled_qty: 0,
+        }
+    }
+
+    pub(crate) fn add_fill(&mut self, fill: Fill) {
+        self.fills.push(fill);
+    }
+
+    pub(crate) fn set_resting(&mut self, order: Order) {
+        self.resting = Some(order);
+    }
+
+    pub(crate) fn cancel(&mut self, qty: u64) {
+        self.cancelled_qty += qty;
+    }
+
+    /// Moves the resting remainder to the cancelled quantity
+    pub(crate) fn cancel_resting(&mut self) {
+        if let Some(order) = self.resting.take() {
+            self.cancel(order.get_order().get_total_qty());
+        }
+    }
+}
+
+impl ExecutionReport {
+    pub fn get_taker_id(&self) -> u64 {
+        self.taker_id
+    }
+
+    pub fn get_qty(&self) -> u64 {
+        self.qty
+    }
+
+    pub fn get_fills(&self) -> &[Fill] {
+        &self.fills
+    }
+
+    /// Sum of all fills
+    pub fn get_filled_qty(&self) -> u64 {
+        self.fills.iter().map(Fill::get_qty).sum()
+    }
+
+    /// Remainder of the order which has been inserted into the orderbook
+    pub fn get_resting(&self) -> Option<&Order> {
+        self.resting.as_ref()
+    }
+
+    pub fn get_resting_qty(&self) -> u64 {
+        self.resting
+            .as_ref()
+            .map_or(0, |order| order.get_order().get_total_qty())
+    }
+
+    pub fn get_cancelled_qty(&self) -> u64 {
+        self.cancelled_qty
+    }
+
+    /// True if the order has been executed completely
+    pub fn is_filled(&self) -> bool {
+        self.get_filled_qty() == self.qty
+    }
+}
@@ -5,12 +5,76 @@ use core::fmt;
 pub struct IdentifiableOrder {
     // This shouldn't be an i64 if it's used for production
     id: u64,
+    // Visible quantity, the current peak of an iceberg order
     qty: u64,
+    // Peak size of an iceberg order
+    display_qty: Option<u64>,
+    // Reserve of an iceberg order, which is not visible in the orderbook
+    hidden_qty: u64,
+    // Time the order has been added to the orderbook, in nanoseconds since the Unix epoch
+    timestamp: u64,
+    // Time the order is removed from the orderbook, in nanoseconds since the Unix epoch
+    expires_at: Option<u64>,
 }
 
 impl IdentifiableOrder {
     pub fn new(id: u64, qty: u64) -> Self {
-        Self { id, qty }
+        Self {
+            id,
+            qty,
+            display_qty: None,
+            hidden_qty: 0,
+            timestamp: 0,
+            expires_at: None,
+        }
+    }
+
+    /// Turns the order into an iceberg order, which only shows the display quantity once it rests in the orderbook.
+    ///
+    /// The display quantity has to be between 1 and the order quantity, otherwise the order is rejected when it is submitted.
+    pub fn with_display_qty(mut self, display_qty: u64) -> Self {
+        self.display_qty = Some(display_qty);
+        self
+    }
+
+    /// Moves everything above the display quantity into the hidden reserve
+    pub(crate) fn hide_reserve(&mut self) {
+        if let Some(display_qty) = self.display_qty {
+            let visible_qty = self.qty.min(display_qty);
+            self.hidden_qty += self.qty - visible_qty;
+            self.qty = visible_qty;
+        }
+    }
+
+    /// Visible and hidden quantity the order would have after hiding its reserve
+    pub(crate) fn split_reserve(&self) -> (u64, u64) {
+        let total_qty = self.get_total_qty();
+        let visible_qty = self.display_qty.map_or(total_qty, |d| total_qty.min(d));
+        (visible_qty, total_qty - visible_qty)
+    }
+
+    /// Shows the next peak of a filled iceberg order.
+    ///
+    /// Returns false if the visible quantity is not filled yet or the reserve is empty.
+    pub(crate) fn replenish(&mut self) -> bool {
+        match self.display_qty {
+            Some(display_qty) if self.qty == 0 && self.hidden_qty > 0 => {
+                self.qty = self.hidden_qty.min(display_qty);
+                self.hidden_qty -= self.qty;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Reduces the open quantity, the hidden reserve is reduced first
+    pub(crate) fn reduce_total_qty(&mut self, total_qty: u64) {
+        if total_qty >= self.qty {
+            self.hidden_qty = total_qty - self.qty;
+        } else {
+            self.qty = total_qty;
+            self.hidden_qty = 0;
+        }
     }
 }
 
@@ -26,6 +90,35 @@ impl IdentifiableOrder {
     pub fn set_qty(&mut self, qty: u64) {
         self.qty = qty;
     }
+
+    pub fn get_display_qty(&self) -> Option<u64> {
+        self.display_qty
+    }
+
+    pub fn get_hidden_qty(&self) -> u64 {
+        self.hidden_qty
+    }
+
+    pub fn get_timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub(crate) fn set_timestamp(&mut self, timestamp: u64) {
+        self.timestamp = timestamp;
+    }
+
+    pub fn get_expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+
+    pub(crate) fn set_expires_at(&mut self, expires_at: Option<u64>) {
+        self.expires_at = expires_at;
+    }
+
+    /// Visible and hidden open quantity
+    pub fn get_total_qty(&self) -> u64 {
+        self.qty.saturating_add(self.hidden_qty)
+    }
 }
 
 impl fmt::Display for IdentifiableOrder {
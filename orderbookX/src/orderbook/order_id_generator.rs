@@ -0,0 +1,30 @@
+/// Monotonic sequence of order ids for orders without a client supplied id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderIdGenerator {
+    // None once u64::MAX has been returned
+    next_id: Option<u64>,
+}
+
+impl Default for OrderIdGenerator {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl OrderIdGenerator {
+    /// Creates a sequence starting with the given id
+    pub fn new(start_id: u64) -> Self {
+        Self {
+            next_id: Some(start_id),
+        }
+    }
+
+    /// Returns the next id of the sequence, every id is returned at most once.
+    ///
+    /// Returns None once the sequence is exhausted.
+    pub fn next_id(&mut self) -> Option<u64> {
+        let id = self.next_id?;
+        self.next_id = id.checked_add(1);
+        Some(id)
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use super::{order_queue::OrderHandle, orders::Order};
+use crate::{price::Price, traits::matching_engine::OrderType};
+
+/// Index of all resting orders by their order id
+pub(crate) type OrderIndex = HashMap<u64, OrderLocation>;
+
+/// Location of a resting order inside of the orderbook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OrderLocation {
+    pub(crate) order_type: OrderType,
+    pub(crate) price: Price,
+    /// Handle in the FIFO queue of the price level, stays valid while the order is resting
+    pub(crate) handle: OrderHandle,
+}
+
+impl OrderLocation {
+    pub(crate) fn new(order_type: OrderType, price: Price, handle: OrderHandle) -> Self {
+        Self {
+            order_type,
+            price,
+            handle,
+        }
+    }
+}
+
+/// Order removed from the orderbook by a cancel request
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CancelledOrder {
+    order: Order,
+}
+
+impl CancelledOrder {
+    pub(crate) fn new(order: Order) -> Self {
+        Self { order }
+    }
+
+    pub fn get_order_type(&self) -> OrderType {
+        self.order.get_order_type()
+    }
+
+    /// Order as it was resting in the orderbook, including its remaining quantity
+    pub fn get_order(&self) -> &Order {
+        &self.order
+    }
+}
@@ -0,0 +1,21 @@
+use std::collections::HashMap;
+
+use super::orders::Order;
+use crate::traits::matching_engine::OcoTrigger;
+
+/// Links of order groups by order id
+pub(crate) type OrderLinks = HashMap<u64, OrderLink>;
+
+/// What happens to other orders once the linked order is executed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum OrderLink {
+    /// Other order of a one-cancels-other pair
+    Oco { other_id: u64, trigger: OcoTrigger },
+    /// Other order of the pair has been executed before this order was submitted
+    OcoCancelled,
+    /// Entry of a bracket order, the exit orders are submitted as an OCO pair once it is filled
+    BracketEntry {
+        take_profit: Box<Order>,
+        stop_loss: Box<Order>,
+    },
+}
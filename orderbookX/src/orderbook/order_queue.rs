@@ -0,0 +1,273 @@
+use super::identifiable_order::IdentifiableOrder;
+
+/// Position of an order inside of its [OrderQueue], valid until the order is removed
+pub(crate) type OrderHandle = usize;
+
+/// FIFO queue of the orders at one price level.
+///
+/// Orders are stored in a slab and linked in time priority order.
+/// Appending, removing the first order, removing any order by its handle and moving the first order to the back are O(1).
+/// Slots of removed orders are reused by later orders.
+/// The visible quantity of the level is kept up to date, orders are only changed through [OrderQueue::update].
+#[derive(Debug, Default, Clone)]
+pub struct OrderQueue {
+    nodes: Vec<Node>,
+    // Slots of removed orders
+    free: Vec<OrderHandle>,
+    head: Option<OrderHandle>,
+    tail: Option<OrderHandle>,
+    len: usize,
+    // Sum of the visible quantities, wide enough to never overflow
+    qty: u128,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    // None once the order has been removed
+    order: Option<IdentifiableOrder>,
+    prev: Option<OrderHandle>,
+    next: Option<OrderHandle>,
+}
+
+impl OrderQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Visible quantity of all orders, saturates at u64::MAX
+    pub fn get_qty(&self) -> u64 {
+        u64::try_from(self.qty).unwrap_or(u64::MAX)
+    }
+
+    /// Appends the order to the back of the queue and returns its handle
+    pub fn push_back(&mut self, order: IdentifiableOrder) -> OrderHandle {
+        self.qty += u128::from(order.get_qty());
+        let node = Node {
+            order: Some(order),
+            prev: None,
+            next: None,
+        };
+        let handle = match self.free.pop() {
+            Some(handle) => {
+                self.nodes[handle] = node;
+                handle
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        self.link_back(handle);
+        self.len += 1;
+        handle
+    }
+
+    /// First order, matched next
+    pub fn front(&self) -> Option<&IdentifiableOrder> {
+        self.get(self.head?)
+    }
+
+    /// Changes the first order, see [OrderQueue::update]
+    pub fn update_front<R>(&mut self, f: impl FnOnce(&mut IdentifiableOrder) -> R) -> Option<R> {
+        self.update(self.head?, f)
+    }
+
+    /// Handle of the last order
+    pub fn back_handle(&self) -> Option<OrderHandle> {
+        self.tail
+    }
+
+    pub fn pop_front(&mut self) -> Option<IdentifiableOrder> {
+        self.remove(self.head?)
+    }
+
+    /// Moves the first order to the back of the queue, its handle stays valid
+    pub fn requeue_front(&mut self) {
+        if let Some(head) = self.head {
+            if self.len > 1 {
+                self.unlink(head);
+                self.link_back(head);
+            }
+        }
+    }
+
+    pub fn get(&self, handle: OrderHandle) -> Option<&IdentifiableOrder> {
+        self.nodes.get(handle)?.order.as_ref()
+    }
+
+    /// Changes the order with the given handle and accounts for its new visible quantity
+    pub fn update<R>(
+        &mut self,
+        handle: OrderHandle,
+        f: impl FnOnce(&mut IdentifiableOrder) -> R,
+    ) -> Option<R> {
+        let order = self.nodes.get_mut(handle)?.order.as_mut()?;
+        let old_qty = order.get_qty();
+        let result = f(order);
+        let new_qty = order.get_qty();
+        self.qty = self.qty - u128::from(old_qty) + u128::from(new_qty);
+        Some(result)
+    }
+
+    /// Removes the order with the given handle, wherever it is in the queue
+    pub fn remove(&mut self, handle: OrderHandle) -> Option<IdentifiableOrder> {
+        let order = self.nodes.get_mut(handle)?.order.take()?;
+        self.unlink(handle);
+        self.free.push(handle);
+        self.len -= 1;
+        self.qty -= u128::from(order.get_qty());
+        Some(order)
+    }
+
+    /// Handle of the first order matching the predicate, searches from the front
+    pub fn find(&self, predicate: impl Fn(&IdentifiableOrder) -> bool) -> Option<OrderHandle> {
+        self.iter_handles()
+            .find(|(_, order)| predicate(order))
+            .map(|(handle, _)| handle)
+    }
+
+    /// Orders in FIFO order
+    pub fn iter(&self) -> impl Iterator<Item = &IdentifiableOrder> {
+        self.iter_handles().map(|(_, order)| order)
+    }
+
+    /// Orders in FIFO order with their handles
+    pub fn iter_handles(&self) -> impl Iterator<Item = (OrderHandle, &IdentifiableOrder)> {
+        let mut next = self.head;
+        std::iter::from_fn(move || {
+            let handle = next?;
+            let node = &self.nodes[handle];
+            next = node.next;
+            Some((handle, node.order.as_ref()?))
+        })
+    }
+
+    fn link_back(&mut self, handle: OrderHandle) {
+        self.nodes[handle].prev = self.tail;
+        self.nodes[handle].next = None;
+        match self.tail {
+            Some(tail) => self.nodes[tail].next = Some(handle),
+            None => self.head = Some(handle),
+        }
+        self.tail = Some(handle);
+    }
+
+    fn unlink(&mut self, handle: OrderHandle) {
+        let (prev, next) = (self.nodes[handle].prev, self.nodes[handle].next);
+        match prev {
+            Some(prev) => self.nodes[prev].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.nodes[next].prev = prev,
+            None => self.tail = prev,
+        }
+        self.nodes[handle].prev = None;
+        self.nodes[handle].next = None;
+    }
+}
+
+impl PartialEq for OrderQueue {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for OrderQueue {}
+
+impl FromIterator<IdentifiableOrder> for OrderQueue {
+    fn from_iter<T: IntoIterator<Item = IdentifiableOrder>>(iter: T) -> Self {
+        let mut queue = Self::new();
+        for order in iter {
+            queue.push_back(order);
+        }
+        queue
+    }
+}
+
+/// Orders in FIFO order
+impl IntoIterator for OrderQueue {
+    type Item = IdentifiableOrder;
+    type IntoIter = std::vec::IntoIter<IdentifiableOrder>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let mut orders = Vec::with_capacity(self.len);
+        while let Some(order) = self.pop_front() {
+            orders.push(order);
+        }
+        orders.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(queue: &OrderQueue) -> Vec<u64> {
+        queue.iter().map(IdentifiableOrder::get_id).collect()
+    }
+
+    /// Handles stay valid while other orders are removed or requeued
+    #[test]
+    fn test_remove_by_handle() {
+        let mut queue = OrderQueue::new();
+        let handles: Vec<OrderHandle> = (1..=4)
+            .map(|id| queue.push_back(IdentifiableOrder::new(id, 100)))
+            .collect();
+
+        assert_eq!(
+            queue.remove(handles[1]).map(|order| order.get_id()),
+            Some(2)
+        );
+        assert_eq!(queue.remove(handles[1]), None);
+        assert_eq!(ids(&queue), vec![1, 3, 4]);
+
+        queue.requeue_front();
+        assert_eq!(ids(&queue), vec![3, 4, 1]);
+        assert_eq!(queue.get(handles[0]).map(|order| order.get_id()), Some(1));
+        assert_eq!(
+            queue.remove(handles[3]).map(|order| order.get_id()),
+            Some(4)
+        );
+        assert_eq!(queue.back_handle(), Some(handles[0]));
+        assert_eq!(queue.len(), 2);
+    }
+
+    /// Visible quantity follows pushes, updates and removals
+    #[test]
+    fn test_qty() {
+        let mut queue: OrderQueue = (1..=3).map(|id| IdentifiableOrder::new(id, 100)).collect();
+        assert_eq!(queue.get_qty(), 300);
+        queue.update_front(|order| order.set_qty(40));
+        assert_eq!(queue.get_qty(), 240);
+        queue.pop_front();
+        assert_eq!(queue.get_qty(), 200);
+
+        queue.push_back(IdentifiableOrder::new(4, u64::MAX));
+        assert_eq!(queue.get_qty(), u64::MAX);
+        queue.pop_front();
+        queue.pop_front();
+        assert_eq!(queue.get_qty(), u64::MAX);
+    }
+
+    /// Slots of removed orders are reused at the back of the queue
+    #[test]
+    fn test_reuse_slots() {
+        let mut queue: OrderQueue = (1..=3).map(|id| IdentifiableOrder::new(id, 100)).collect();
+        assert_eq!(queue.pop_front().map(|order| order.get_id()), Some(1));
+        let handle = queue.push_back(IdentifiableOrder::new(4, 100));
+        assert_eq!(handle, 0);
+        assert_eq!(ids(&queue), vec![2, 3, 4]);
+
+        let orders: Vec<u64> = queue.into_iter().map(|order| order.get_id()).collect();
+        assert_eq!(orders, vec![2, 3, 4]);
+    }
+}
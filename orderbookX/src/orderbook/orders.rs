@@ -1,29 +1,97 @@
 use core::fmt;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashSet};
 
-use indexmap::IndexMap;
-
-use super::identifiable_order::IdentifiableOrder;
-use crate::price::Price;
+use super::{identifiable_order::IdentifiableOrder, order_queue::OrderQueue};
+use crate::{
+    price::{Notional, Price, PriceError},
+    traits::matching_engine::{OrderKind, OrderType, TimeInForce},
+};
 
+/// Incoming order with its side, limit price and execution instructions
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Order {
+    order_type: OrderType,
     price: Price,
     identifiable_order: IdentifiableOrder,
+    kind: OrderKind,
+    time_in_force: TimeInForce,
+    // Only used by stop orders
+    stop_price: Option<Price>,
 }
 
 impl Order {
-    pub fn new(price: Price, identifiable_order: IdentifiableOrder) -> Self {
+    /// Creates a Good till Cancel limit order
+    pub fn new(order_type: OrderType, price: Price, identifiable_order: IdentifiableOrder) -> Self {
         Self {
+            order_type,
             price,
             identifiable_order,
+            kind: OrderKind::default(),
+            time_in_force: TimeInForce::default(),
+            stop_price: None,
         }
     }
 
+    /// Creates a Good till Cancel limit buy order
+    pub fn buy(price: Price, identifiable_order: IdentifiableOrder) -> Self {
+        Self::new(OrderType::Buy, price, identifiable_order)
+    }
+
+    /// Creates a Good till Cancel limit sell order
+    pub fn sell(price: Price, identifiable_order: IdentifiableOrder) -> Self {
+        Self::new(OrderType::Sell, price, identifiable_order)
+    }
+
+    pub fn with_kind(mut self, kind: OrderKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
+    /// Price at which a stop order is triggered
+    pub fn with_stop_price(mut self, stop_price: Price) -> Self {
+        self.stop_price = Some(stop_price);
+        self
+    }
+
+    /// Order which is executed once the stop order has been triggered
+    pub(crate) fn triggered(mut self) -> Self {
+        self.kind = match self.kind {
+            OrderKind::StopMarket => OrderKind::Market,
+            OrderKind::StopLimit => OrderKind::Limit,
+            kind => kind,
+        };
+        self
+    }
+
+    pub fn get_order_type(&self) -> OrderType {
+        self.order_type
+    }
+
+    pub fn get_kind(&self) -> OrderKind {
+        self.kind
+    }
+
+    pub fn get_time_in_force(&self) -> TimeInForce {
+        self.time_in_force
+    }
+
     pub fn get_price(&self) -> &Price {
         &self.price
     }
 
+    pub fn get_stop_price(&self) -> Option<&Price> {
+        self.stop_price.as_ref()
+    }
+
+    pub fn set_price(&mut self, price: Price) {
+        self.price = price;
+    }
+
     pub fn get_order_mut(&mut self) -> &mut IdentifiableOrder {
         &mut self.identifiable_order
     }
@@ -33,13 +101,15 @@ impl Order {
     }
 }
 
-/// IndexMap to keep track of all orders
-type Orders = IndexMap<Price, VecDeque<IdentifiableOrder>>;
+/// BTreeMap to keep track of all orders, always sorted by price
+pub type Orders = BTreeMap<Price, OrderQueue>;
 
 /// OrderList represents sell-side or buy-side for a specific financial instrument.
-/// It uses an IndexMap data structure [Orders] where the keys are prices (f64) for orders and the values are vectors (Vec) of orders (IdentifiableOrder) at that price.
-/// The vector is a time priority list for orders at the given price, where the first element is the first order to be matched.
-/// Together with the price as a key in the IndexMap, two OrderList result in a price/time priority orderbook
+/// It uses a BTreeMap data structure [Orders] where the keys are prices for orders and the values are FIFO queues ([OrderQueue]) of orders (IdentifiableOrder) at that price.
+/// The queue is a time priority list for orders at the given price, where the first element is the first order to be matched.
+/// Together with the price as a key in the BTreeMap, two OrderList result in a price/time priority orderbook
+///
+/// Price levels are kept in ascending order, inserting or removing a price level is O(log n) and never re-sorts the map.
 #[derive(Default, Debug)]
 pub struct OrderList {
     pub order_list: Orders,
@@ -47,34 +117,169 @@ pub struct OrderList {
 
 impl OrderList {
     /// Inserts a limit order at the right price and fifo queue position
-    pub fn insert_order(&mut self, order: Order) {
+    ///
+    /// Returns true if a new price level has been created
+    pub fn insert_order(&mut self, order: Order) -> bool {
         // Check if Price level exists
         if let Some(orders_on_price_level) = self.order_list.get_mut(&order.price) {
             // Add order to existing price level FIFO Queue
-            orders_on_price_level.push_back(order.identifiable_order) // O(1)
+            orders_on_price_level.push_back(order.identifiable_order); // O(1)
+            false
         } else {
-            // Create new price level
-            let mut new_fifo_queue = VecDeque::with_capacity(2);
+            // Create new price level at the correct position
+            let mut new_fifo_queue = OrderQueue::new();
             new_fifo_queue.push_back(order.identifiable_order);
-            self.order_list.insert(order.price, new_fifo_queue); // O(1)
+            self.order_list.insert(order.price, new_fifo_queue); // O(log n)
+            true
+        }
+    }
 
-            /*
-            Sort the Indexmap, so that the new price level is at the correct position
-            Keys will never exist twice, so unstable sort is possible
-            Uses Rayon parallelization
-            */
+    /// Lowest price level
+    pub fn first(&self) -> Option<(&Price, &OrderQueue)> {
+        self.order_list.first_key_value() // O(log n)
+    }
+
+    /// Highest price level
+    pub fn last(&self) -> Option<(&Price, &OrderQueue)> {
+        self.order_list.last_key_value() // O(log n)
+    }
+
+    /// Lowest price level
+    pub fn first_mut(&mut self) -> Option<(&Price, &mut OrderQueue)> {
+        self.order_list.iter_mut().next() // O(log n)
+    }
+
+    /// Highest price level
+    pub fn last_mut(&mut self) -> Option<(&Price, &mut OrderQueue)> {
+        self.order_list.iter_mut().next_back() // O(log n)
+    }
+
+    /// Removes the price level with all of its orders
+    pub fn remove_price_level(&mut self, key: &Price) -> Option<OrderQueue> {
+        self.order_list.remove(key) // O(log n)
+    }
+
+    /// Read-only walk over the ask side, starting at the lowest ask.
+    /// Accumulates the quantity of all price levels at or below the limit price.
+    ///
+    /// Stops as soon as the needed quantity is reached, so the result is capped at the first level that covers it.
+    /// `other_leg` returns the other order of a linked pair, only the first order of a pair is counted.
+    pub fn ask_liquidity_until(
+        &self,
+        limit: &Price,
+        needed_qty: u64,
+        other_leg: impl Fn(u64) -> Option<u64>,
+    ) -> u64 {
+        Self::liquidity_until(
+            self.order_list
+                .iter()
+                .take_while(|(price, _)| *price <= limit),
+            needed_qty,
+            other_leg,
+        )
+    }
+
+    /// Read-only walk over the bid side, starting at the highest bid.
+    /// Accumulates the quantity of all price levels at or above the limit price.
+    ///
+    /// Stops as soon as the needed quantity is reached, so the result is capped at the first level that covers it.
+    /// `other_leg` returns the other order of a linked pair, only the first order of a pair is counted.
+    pub fn bid_liquidity_until(
+        &self,
+        limit: &Price,
+        needed_qty: u64,
+        other_leg: impl Fn(u64) -> Option<u64>,
+    ) -> u64 {
+        Self::liquidity_until(
+            self.order_list
+                .iter()
+                .rev()
+                .take_while(|(price, _)| *price >= limit),
+            needed_qty,
+            other_leg,
+        )
+    }
+
+    /// Read-only walk over the ask side, starting at the lowest ask.
+    /// Value of the fills a market buy order of the given quantity would receive.
+    pub fn ask_notional_for(
+        &self,
+        qty: u64,
+        other_leg: impl Fn(u64) -> Option<u64>,
+    ) -> Result<Notional, PriceError> {
+        Self::notional_for(self.order_list.iter(), qty, other_leg)
+    }
+
+    /// Read-only walk over the bid side, starting at the highest bid.
+    /// Value of the fills a market sell order of the given quantity would receive.
+    pub fn bid_notional_for(
+        &self,
+        qty: u64,
+        other_leg: impl Fn(u64) -> Option<u64>,
+    ) -> Result<Notional, PriceError> {
+        Self::notional_for(self.order_list.iter().rev(), qty, other_leg)
+    }
+
+    /// Orders in matching order, without the orders which are cancelled once the other order of their pair is executed
+    fn executable_orders<'a>(
+        price_levels: impl Iterator<Item = (&'a Price, &'a OrderQueue)>,
+        other_leg: impl Fn(u64) -> Option<u64>,
+    ) -> impl Iterator<Item = (&'a Price, &'a IdentifiableOrder)> {
+        let mut cancelled = HashSet::new();
+        price_levels
+            .flat_map(|(price, orders)| orders.iter().map(move |order| (price, order)))
+            .filter(move |(_, order)| {
+                if cancelled.remove(&order.get_id()) {
+                    return false;
+                }
+                if let Some(other_id) = other_leg(order.get_id()) {
+                    cancelled.insert(other_id);
+                }
+                true
+            })
+    }
+
+    fn notional_for<'a>(
+        price_levels: impl Iterator<Item = (&'a Price, &'a OrderQueue)>,
+        mut qty: u64,
+        other_leg: impl Fn(u64) -> Option<u64>,
+    ) -> Result<Notional, PriceError> {
+        let mut notional = Notional::default();
+        for (price, order) in Self::executable_orders(price_levels, other_leg) {
+            let fill_qty = order.get_total_qty().min(qty);
+            notional = notional
+                .checked_add(&price.checked_mul_qty(fill_qty)?)
+                .ok_or(PriceError::Overflow)?;
+            qty -= fill_qty;
+            if qty == 0 {
+                break;
+            }
+        }
+        Ok(notional)
+    }
 
-            self.order_list.par_sort_unstable_keys(); // O(n log n + c)
+    fn liquidity_until<'a>(
+        price_levels: impl Iterator<Item = (&'a Price, &'a OrderQueue)>,
+        needed_qty: u64,
+        other_leg: impl Fn(u64) -> Option<u64>,
+    ) -> u64 {
+        let mut accumulator: u64 = 0;
+        for (_, order) in Self::executable_orders(price_levels, other_leg) {
+            accumulator = accumulator.saturating_add(order.get_total_qty());
+            if accumulator >= needed_qty {
+                return accumulator;
+            }
         }
+        accumulator
     }
 }
 
 impl fmt::Display for OrderList {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut vector: Vec<String> = vec![];
-        for price_level in self.order_list.clone().iter_mut() {
+        for price_level in self.order_list.iter() {
             let mut size_on_price_level: u64 = 0;
-            for element in price_level.1.make_contiguous().iter() {
+            for element in price_level.1.iter() {
                 size_on_price_level += element.get_qty();
             }
             vector.push(format!(
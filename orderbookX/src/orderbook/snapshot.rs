@@ -0,0 +1,93 @@
+use super::order_queue::OrderQueue;
+use crate::{price::Price, traits::matching_engine::OrderType};
+
+/// Single resting order of the level-3 view of the orderbook
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RestingOrder {
+    order_id: u64,
+    order_type: OrderType,
+    price: Price,
+    qty: u64,
+    // Position in the FIFO queue of its price level, 0 is matched next
+    queue_position: usize,
+}
+
+impl RestingOrder {
+    pub fn new(
+        order_id: u64,
+        order_type: OrderType,
+        price: Price,
+        qty: u64,
+        queue_position: usize,
+    ) -> Self {
+        Self {
+            order_id,
+            order_type,
+            price,
+            qty,
+            queue_position,
+        }
+    }
+
+    /// Every order of a price level in FIFO order
+    pub(crate) fn from_level<'a>(
+        order_type: OrderType,
+        (price, orders): (&'a Price, &'a OrderQueue),
+    ) -> impl Iterator<Item = Self> + 'a {
+        orders
+            .iter()
+            .enumerate()
+            .map(move |(queue_position, order)| {
+                Self::new(
+                    order.get_id(),
+                    order_type,
+                    price.clone(),
+                    order.get_qty(),
+                    queue_position,
+                )
+            })
+    }
+
+    pub fn get_order_id(&self) -> u64 {
+        self.order_id
+    }
+
+    pub fn get_order_type(&self) -> OrderType {
+        self.order_type
+    }
+
+    pub fn get_price(&self) -> &Price {
+        &self.price
+    }
+
+    pub fn get_qty(&self) -> u64 {
+        self.qty
+    }
+
+    pub fn get_queue_position(&self) -> usize {
+        self.queue_position
+    }
+}
+
+/// Level-3 view of the orderbook, every resting order of both sides in priority order
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Snapshot {
+    // Highest bid first
+    bids: Vec<RestingOrder>,
+    // Lowest ask first
+    asks: Vec<RestingOrder>,
+}
+
+impl Snapshot {
+    pub fn new(bids: Vec<RestingOrder>, asks: Vec<RestingOrder>) -> Self {
+        Self { bids, asks }
+    }
+
+    pub fn get_bids(&self) -> &[RestingOrder] {
+        &self.bids
+    }
+
+    pub fn get_asks(&self) -> &[RestingOrder] {
+        &self.asks
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use super::orders::Order;
+use crate::{price::Price, traits::matching_engine::OrderType};
+
+/// Stop orders waiting for their stop price, keyed by stop price with a FIFO queue per stop price
+#[derive(Debug, Default)]
+pub(crate) struct StopBook {
+    // Triggered at or above their stop price, lowest stop price first
+    buy_stops: BTreeMap<Price, VecDeque<Order>>,
+    // Triggered at or below their stop price, highest stop price first
+    sell_stops: BTreeMap<Price, VecDeque<Order>>,
+    // id -> side and stop price of every waiting stop order
+    index: HashMap<u64, (OrderType, Price)>,
+}
+
+impl StopBook {
+    /// Adds a validated stop order to the back of the queue of its stop price
+    pub(crate) fn insert(&mut self, stop_price: Price, order: Order) {
+        let order_type = order.get_order_type();
+        self.index
+            .insert(order.get_order().get_id(), (order_type, stop_price.clone()));
+        self.stops_mut(order_type)
+            .entry(stop_price)
+            .or_default()
+            .push_back(order);
+    }
+
+    pub(crate) fn contains(&self, order_id: u64) -> bool {
+        self.index.contains_key(&order_id)
+    }
+
+    pub(crate) fn get(&self, order_id: u64) -> Option<&Order> {
+        let (order_type, stop_price) = self.index.get(&order_id)?;
+        let stops = match order_type {
+            OrderType::Buy => &self.buy_stops,
+            OrderType::Sell => &self.sell_stops,
+        };
+        stops
+            .get(stop_price)?
+            .iter()
+            .find(|order| order.get_order().get_id() == order_id)
+    }
+
+    pub(crate) fn remove(&mut self, order_id: u64) -> Option<Order> {
+        let (order_type, stop_price) = self.index.remove(&order_id)?;
+        let stops = self.stops_mut(order_type);
+        let orders = stops.get_mut(&stop_price)?;
+        let position = orders
+            .iter()
+            .position(|order| order.get_order().get_id() == order_id)?;
+        let removed = orders.remove(position);
+        if orders.is_empty() {
+            stops.remove(&stop_price);
+        }
+        removed
+    }
+
+    /// Removes the next stop order triggered by the last traded price.
+    ///
+    /// Buy stops are triggered before sell stops, the stop price furthest beyond the last traded price first.
+    pub(crate) fn pop_triggered(&mut self, last_traded_price: &Price) -> Option<Order> {
+        let (order_type, stop_price) = if let Some((stop_price, _)) = self
+            .buy_stops
+            .first_key_value()
+            .filter(|(stop_price, _)| *stop_price <= last_traded_price)
+        {
+            (OrderType::Buy, stop_price.clone())
+        } else {
+            let (stop_price, _) = self
+                .sell_stops
+                .last_key_value()
+                .filter(|(stop_price, _)| *stop_price >= last_traded_price)?;
+            (OrderType::Sell, stop_price.clone())
+        };
+        let stops = self.stops_mut(order_type);
+        let orders = stops.get_mut(&stop_price)?;
+        let triggered = orders.pop_front()?;
+        if orders.is_empty() {
+            stops.remove(&stop_price);
+        }
+        self.index.remove(&triggered.get_order().get_id());
+        Some(triggered)
+    }
+
+    /// Waiting buy stops in trigger order
+    pub(crate) fn iter_buys(&self) -> impl Iterator<Item = &Order> {
+        self.buy_stops.values().flatten()
+    }
+
+    /// Waiting sell stops in trigger order
+    pub(crate) fn iter_sells(&self) -> impl Iterator<Item = &Order> {
+        self.sell_stops.values().rev().flatten()
+    }
+
+    fn stops_mut(&mut self, order_type: OrderType) -> &mut BTreeMap<Price, VecDeque<Order>> {
+        match order_type {
+            OrderType::Buy => &mut self.buy_stops,
+            OrderType::Sell => &mut self.sell_stops,
+        }
+    }
+}
@@ -1,92 +1,435 @@
 use core::fmt;
 use std::{
     cmp::Ordering,
+    error::Error,
     hash::{Hash, Hasher},
+    str::FromStr,
 };
 
-#[derive(Eq, PartialEq, Debug, Clone)]
+/// Highest supported amount of decimal places
+pub const MAX_SCALE: u8 = 18;
+
+/// Fixed-point price, `units` in steps of `10^-scale`.
+///
+/// Prices with a different scale but the same value are equal, e.g. 1.5 and 1.50.
+#[derive(Debug, Clone)]
 pub struct Price {
-    main_unit: usize,
-    // clamp this between 0 and 99
-    sub_unit: u8,
+    units: u128,
+    // Number of decimal places
+    scale: u8,
 }
 
+/// Prints all decimal places of the scale, e.g. `10.05` or `10.50` for two decimal places
 impl fmt::Display for Price {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}.{}", self.main_unit, self.sub_unit)
+        write_fixed_point(f, self.units, self.scale)
+    }
+}
+
+fn write_fixed_point(f: &mut fmt::Formatter<'_>, units: u128, scale: u8) -> fmt::Result {
+    if scale == 0 {
+        write!(f, "{}", units)
+    } else {
+        write!(
+            f,
+            "{}.{:0width$}",
+            units / pow10(scale),
+            units % pow10(scale),
+            width = scale as usize
+        )
     }
 }
 
+/// Closest f64 to the fixed-point value
+fn fixed_point_to_f64(units: u128, scale: u8) -> f64 {
+    // Both operands are exact below 2^53, so the division is correctly rounded
+    if units < 1 << f64::MANTISSA_DIGITS {
+        units as f64 / pow10(scale) as f64
+    } else {
+        // Parsing is correctly rounded as well
+        format!("{}e-{}", units, scale)
+            .parse()
+            .unwrap_or(f64::INFINITY)
+    }
+}
+
+/// Rescales both values to the larger scale.
+///
+/// Returns None if one of them can not be represented at that scale.
+fn common_scale(a: (u128, u8), b: (u128, u8)) -> Option<(u128, u128, u8)> {
+    let scale = a.1.max(b.1);
+    Some((
+        a.0.checked_mul(pow10(scale - a.1))?,
+        b.0.checked_mul(pow10(scale - b.1))?,
+        scale,
+    ))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PriceError {
+    /// Price has to be at least one unit of its scale
+    Zero,
+    /// Sub unit has to be between 0 and 99
+    SubUnitOutOfRange(u8),
+    /// Scale is above [MAX_SCALE]
+    ScaleTooLarge(u8),
+    /// Price is too large to be represented
+    Overflow,
+    /// Price has more decimal places than the instrument allows
+    TooPrecise { scale: u8 },
+    /// Price is not a multiple of the tick size of the instrument
+    OffTick,
+    /// String is not a plain decimal number like `10.05`
+    Malformed,
+}
+
+impl fmt::Display for PriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceError::Zero => write!(f, "price must be greater than zero"),
+            PriceError::SubUnitOutOfRange(sub_unit) => {
+                write!(f, "sub unit {} is not between 0 and 99", sub_unit)
+            }
+            PriceError::ScaleTooLarge(scale) => {
+                write!(f, "scale {} is above the maximum of {}", scale, MAX_SCALE)
+            }
+            PriceError::Overflow => write!(f, "price is too large to be represented"),
+            PriceError::TooPrecise { scale } => {
+                write!(f, "price has more than {} decimal places", scale)
+            }
+            PriceError::OffTick => write!(f, "price is not a multiple of the tick size"),
+            PriceError::Malformed => write!(f, "price is not a plain decimal number"),
+        }
+    }
+}
+
+impl Error for PriceError {}
+
+/// 10^scale
+const fn pow10(scale: u8) -> u128 {
+    10u128.pow(scale as u32)
+}
+
 impl Price {
-    /// Create a new Price, does not allow 0.00. Expects correct handling.
+    /// Create a new Price from units of `10^-scale`, e.g. `from_units(1025, 2)` is 10.25.
+    ///
+    /// Rejects zero, scales above [MAX_SCALE] and values which can not be compared with prices of other scales.
+    pub fn from_units(units: u128, scale: u8) -> Result<Self, PriceError> {
+        if scale > MAX_SCALE {
+            Err(PriceError::ScaleTooLarge(scale))
+        } else if units == 0 {
+            Err(PriceError::Zero)
+        } else if units > u128::MAX / pow10(MAX_SCALE - scale) {
+            Err(PriceError::Overflow)
+        } else {
+            Ok(Self { units, scale })
+        }
+    }
+
+    /// Create a new Price with two decimal places, rejects 0.00 and sub unit values above 99.
+    pub fn try_new(main_unit: usize, sub_unit: u8) -> Result<Self, PriceError> {
+        if sub_unit > 99 {
+            Err(PriceError::SubUnitOutOfRange(sub_unit))
+        } else {
+            Self::from_units(main_unit as u128 * 100 + sub_unit as u128, 2)
+        }
+    }
+
+    /// Create a new Price with two decimal places, does not allow 0.00. Expects correct handling.
     ///
     /// Will default into 0.01 if Zero is provided.
     /// Will clamp the sub unit value between the allowed amount.
+    /// Use [Price::try_new] for unvalidated input.
     pub fn new(main_unit: usize, sub_unit: u8) -> Self {
         let sub_unit = sub_unit.clamp(0, 99);
-        // Price has to be at least 0.01
-        if main_unit > 0 || sub_unit > 0 {
-            Self {
-                main_unit,
-                sub_unit,
-            }
+        // Price has to be at least 0.01, usize::MAX always fits into 2 decimal places
+        Self {
+            units: (main_unit as u128 * 100 + sub_unit as u128).max(1),
+            scale: 2,
+        }
+    }
+
+    /// Price in units of `10^-scale`
+    pub fn get_units(&self) -> u128 {
+        self.units
+    }
+
+    /// Number of decimal places
+    pub fn get_scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Integer part of the price
+    pub fn get_main_unit(&self) -> u128 {
+        self.units / pow10(self.scale)
+    }
+
+    /// Decimal part of the price in units of `10^-scale`
+    pub fn get_sub_unit(&self) -> u128 {
+        self.units % pow10(self.scale)
+    }
+
+    /// Returns the same price with the given amount of decimal places.
+    ///
+    /// Fails instead of rounding if the price has more significant decimal places than `scale`.
+    pub fn rescale(&self, scale: u8) -> Result<Self, PriceError> {
+        if scale > MAX_SCALE {
+            return Err(PriceError::ScaleTooLarge(scale));
+        }
+        if scale >= self.scale {
+            Self::from_units(self.units * pow10(scale - self.scale), scale)
         } else {
-            Self {
-                main_unit: 0,
-                sub_unit: 1,
+            let divisor = pow10(self.scale - scale);
+            if !self.units.is_multiple_of(divisor) {
+                Err(PriceError::TooPrecise { scale })
+            } else {
+                Self::from_units(self.units / divisor, scale)
             }
         }
     }
 
-    pub fn get_price_as_f64() -> f64 {
-        todo!()
+    /// Returns the price one unit of its scale (e.g. 0.01 for two decimal places) above.
+    ///
+    /// Returns None if the price can not be represented anymore.
+    pub fn next_tick(&self) -> Option<Self> {
+        Self::from_units(self.units.checked_add(1)?, self.scale).ok()
+    }
+
+    /// Returns the price one unit of its scale (e.g. 0.01 for two decimal places) below.
+    ///
+    /// Returns None if the price is already the lowest possible price of its scale.
+    pub fn prev_tick(&self) -> Option<Self> {
+        Self::from_units(self.units - 1, self.scale).ok()
+    }
+
+    /// Returns the sum of both prices with the larger scale of both.
+    ///
+    /// Returns None if the sum can not be represented.
+    pub fn checked_add(&self, other: &Price) -> Option<Self> {
+        let (units, other_units, scale) =
+            common_scale((self.units, self.scale), (other.units, other.scale))?;
+        Self::from_units(units.checked_add(other_units)?, scale).ok()
+    }
+
+    /// Returns the difference of both prices with the larger scale of both, e.g. the spread between ask and bid.
+    ///
+    /// Returns None if the difference is not positive.
+    pub fn checked_sub(&self, other: &Price) -> Option<Self> {
+        let (units, other_units, scale) =
+            common_scale((self.units, self.scale), (other.units, other.scale))?;
+        Self::from_units(units.checked_sub(other_units)?, scale).ok()
+    }
+
+    /// Returns the price exactly halfway between both prices, e.g. the mid price between bid and ask.
+    ///
+    /// Uses one more decimal place than the larger scale of both if needed.
+    /// Returns None if the result can not be represented.
+    pub fn checked_midpoint(&self, other: &Price) -> Option<Self> {
+        let (units, other_units, scale) =
+            common_scale((self.units, self.scale), (other.units, other.scale))?;
+        let sum = units.checked_add(other_units)?;
+        if sum.is_multiple_of(2) {
+            Self::from_units(sum / 2, scale).ok()
+        } else {
+            Self::from_units(sum.checked_mul(5)?, scale.checked_add(1)?).ok()
+        }
+    }
+
+    /// Returns the price `ticks` steps of `tick_size` above.
+    ///
+    /// Returns None if the price can not be represented anymore.
+    pub fn checked_add_ticks(&self, ticks: u64, tick_size: &Price) -> Option<Self> {
+        let (units, tick_units, scale) =
+            common_scale((self.units, self.scale), (tick_size.units, tick_size.scale))?;
+        let units = units.checked_add(tick_units.checked_mul(ticks as u128)?)?;
+        Self::from_units(units, scale).ok()
+    }
+
+    /// Returns the price `ticks` steps of `tick_size` below.
+    ///
+    /// Returns None if there is no positive price `ticks` steps below.
+    pub fn checked_sub_ticks(&self, ticks: u64, tick_size: &Price) -> Option<Self> {
+        let (units, tick_units, scale) =
+            common_scale((self.units, self.scale), (tick_size.units, tick_size.scale))?;
+        let units = units.checked_sub(tick_units.checked_mul(ticks as u128)?)?;
+        Self::from_units(units, scale).ok()
+    }
+
+    /// Value of `qty` units at this price, with the scale of the price.
+    pub fn checked_mul_qty(&self, qty: u64) -> Result<Notional, PriceError> {
+        let units = self
+            .units
+            .checked_mul(qty as u128)
+            .ok_or(PriceError::Overflow)?;
+        Ok(Notional::from_units(units, self.scale))
+    }
+
+    /// Closest f64 to the price, for display and analytics only.
+    ///
+    /// Prices of orders should never be calculated with floats.
+    pub fn to_f64(&self) -> f64 {
+        fixed_point_to_f64(self.units, self.scale)
+    }
+
+    /// Value at the highest supported scale, used to compare prices of different scales
+    fn normalized(&self) -> u128 {
+        // Can not overflow, checked on construction
+        self.units * pow10(MAX_SCALE - self.scale)
+    }
+}
+
+/// Fixed-point value of a quantity at a price, e.g. the exposure of an order.
+///
+/// Unlike [Price] a notional can be zero.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Notional {
+    units: u128,
+    // Number of decimal places
+    scale: u8,
+}
+
+impl Notional {
+    /// Create a new Notional from units of `10^-scale`
+    pub fn from_units(units: u128, scale: u8) -> Self {
+        Self { units, scale }
+    }
+
+    /// Notional in units of `10^-scale`
+    pub fn get_units(&self) -> u128 {
+        self.units
+    }
+
+    /// Number of decimal places
+    pub fn get_scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Returns the sum of both notionals with the larger scale of both.
+    ///
+    /// Returns None on overflow.
+    pub fn checked_add(&self, other: &Notional) -> Option<Self> {
+        let (units, other_units, scale) =
+            common_scale((self.units, self.scale), (other.units, other.scale))?;
+        Some(Self::from_units(units.checked_add(other_units)?, scale))
+    }
+
+    /// Closest f64 to the notional, for display and analytics only.
+    pub fn to_f64(&self) -> f64 {
+        fixed_point_to_f64(self.units, self.scale)
+    }
+}
+
+impl fmt::Display for Notional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fixed_point(f, self.units, self.scale)
+    }
+}
+
+impl PartialEq for Notional {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Notional {}
+
+impl Ord for Notional {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match common_scale((self.units, self.scale), (other.units, other.scale)) {
+            Some((units, other_units, _)) => units.cmp(&other_units),
+            // The value with the smaller scale overflowed while rescaling, so it is the larger one
+            None if self.scale < other.scale => Ordering::Greater,
+            None => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for Notional {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Parses a plain decimal number without going through floats.
+///
+/// The scale is the number of decimal places in the string, e.g. `"10.50"` has a scale of 2.
+/// Signs, exponents, whitespace and missing digits on either side of the decimal point are rejected.
+impl FromStr for Price {
+    type Err = PriceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (main_unit, sub_unit) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if main_unit.is_empty()
+            || !is_digits(main_unit)
+            || !is_digits(sub_unit)
+            || (s.contains('.') && sub_unit.is_empty())
+        {
+            return Err(PriceError::Malformed);
+        }
+        let scale = u8::try_from(sub_unit.len()).map_err(|_| PriceError::ScaleTooLarge(u8::MAX))?;
+        if scale > MAX_SCALE {
+            return Err(PriceError::ScaleTooLarge(scale));
+        }
+
+        let units = main_unit
+            .bytes()
+            .chain(sub_unit.bytes())
+            .try_fold(0u128, |units, digit| {
+                units.checked_mul(10)?.checked_add((digit - b'0') as u128)
+            })
+            .ok_or(PriceError::Overflow)?;
+        Self::from_units(units, scale)
     }
 }
 
-/// Rounds the sub_unit to two decimal places
+/// Rounds to two decimal places and clamps into the representable range
+fn f64_to_cents(value: f64) -> u128 {
+    // Float to int casts saturate, NaN becomes 0
+    ((value * 100.0).round() as u128).clamp(1, u128::MAX / pow10(MAX_SCALE - 2))
+}
+
+/// Rounds to two decimal places
 impl From<f64> for Price {
     /// Will default into 0.01 if Zero is provided.
+    /// Will clamp values too large to be represented to the highest price.
     fn from(value: f64) -> Self {
-        let value = if value == 0.00 { 0.01 } else { value };
-
-        let main_unit = value as usize;
-        let sub_unit = ((value - main_unit as f64) * 100.0).round() as u8;
-            Price {
-                main_unit,
-                sub_unit,
-            }
+        Price {
+            units: f64_to_cents(value),
+            scale: 2,
+        }
     }
 }
 
-/// Rounds the sub_unit to two decimal places
+/// Rounds to two decimal places
 impl From<f32> for Price {
     /// Will default into 0.01 if Zero is provided.
+    /// Will clamp values too large to be represented to the highest price.
     fn from(value: f32) -> Self {
-        let value = if value == 0.00 { 0.01 } else { value };
-        let main_unit = value as usize;
-        let sub_unit = ((value - main_unit as f32) * 100.0).round() as u8;
-            Price {
-                main_unit,
-                sub_unit,
-            }
+        Price {
+            units: f64_to_cents(value as f64),
+            scale: 2,
+        }
+    }
+}
 
+impl PartialEq for Price {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
     }
 }
 
+impl Eq for Price {}
+
 impl Hash for Price {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.main_unit.hash(state);
-        self.sub_unit.hash(state);
+        self.normalized().hash(state);
     }
 }
 
 impl Ord for Price {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self.main_unit.cmp(&other.main_unit) {
-            Ordering::Less => Ordering::Less,
-            Ordering::Greater => Ordering::Greater,
-            Ordering::Equal => self.sub_unit.cmp(&other.sub_unit),
-        }
+        self.normalized().cmp(&other.normalized())
     }
 }
 
@@ -98,85 +441,127 @@ impl PartialOrd for Price {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn test_new_with_positive_units() {
         let price = Price::new(10, 50);
-        assert_eq!(price.main_unit, 10);
-        assert_eq!(price.sub_unit, 50);
+        assert_eq!(price.get_main_unit(), 10);
+        assert_eq!(price.get_sub_unit(), 50);
     }
 
     #[test]
     fn test_new_with_zero_units() {
         let price = Price::new(0, 0);
-        assert_eq!(price.main_unit, 0);
-        assert_eq!(price.sub_unit, 1); // sub_unit should be at least 1
+        assert_eq!(price.get_main_unit(), 0);
+        assert_eq!(price.get_sub_unit(), 1); // sub_unit should be at least 1
     }
 
     #[test]
     fn test_new_with_high_sub_units() {
         let price = Price::new(100, 150);
-        assert_eq!(price.main_unit, 100);
-        assert_eq!(price.sub_unit, 99); // sub_unit should be clamped to 99 at max
+        assert_eq!(price.get_main_unit(), 100);
+        assert_eq!(price.get_sub_unit(), 99); // sub_unit should be clamped to 99 at max
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert_eq!(Price::try_new(10, 50), Ok(Price::new(10, 50)));
+        assert_eq!(Price::try_new(0, 1), Ok(Price::new(0, 1)));
+        assert_eq!(Price::try_new(0, 0), Err(PriceError::Zero));
+        assert_eq!(
+            Price::try_new(100, 150),
+            Err(PriceError::SubUnitOutOfRange(150))
+        );
     }
 
     #[test]
     fn test_f64_into_price() {
         let price: Price = 10.99.into();
-        assert_eq!(price.main_unit, 10);
-        assert_eq!(price.sub_unit, 99);
+        assert_eq!(price.get_main_unit(), 10);
+        assert_eq!(price.get_sub_unit(), 99);
     }
 
     #[test]
     fn test_f32_into_price() {
         let price: Price = 580.37.into();
-        assert_eq!(price.main_unit, 580);
-        assert_eq!(price.sub_unit, 37);
+        assert_eq!(price.get_main_unit(), 580);
+        assert_eq!(price.get_sub_unit(), 37);
     }
 
     #[test]
     fn test_f64_into_price_with_zero_units() {
         let price: Price = 0.00.into();
-        assert_eq!(price.main_unit, 0);
-        assert_eq!(price.sub_unit, 1); // sub_unit should be at least 1
+        assert_eq!(price.get_main_unit(), 0);
+        assert_eq!(price.get_sub_unit(), 1); // sub_unit should be at least 1
     }
 
     #[test]
     fn test_f32_into_price_with_zero_units() {
         let price: Price = 0.00.into();
-        assert_eq!(price.main_unit, 0);
-        assert_eq!(price.sub_unit, 1); // sub_unit should be at least 1
+        assert_eq!(price.get_main_unit(), 0);
+        assert_eq!(price.get_sub_unit(), 1); // sub_unit should be at least 1
     }
 
     #[test]
     fn test_f64_sub_unit_into_price() {
         let price: Price = 66.123.into();
-        assert_eq!(price.main_unit, 66);
-        assert_eq!(price.sub_unit, 12);
+        assert_eq!(price.get_main_unit(), 66);
+        assert_eq!(price.get_sub_unit(), 12);
     }
 
     #[test]
     fn test_f32_sub_unit_into_price() {
         let price: Price = 580.123.into();
-        assert_eq!(price.main_unit, 580);
-        assert_eq!(price.sub_unit, 12);
+        assert_eq!(price.get_main_unit(), 580);
+        assert_eq!(price.get_sub_unit(), 12);
+    }
+
+    #[test]
+    fn test_f64_into_price_clamps_overflow() {
+        let highest = Price::from_units(u128::MAX / pow10(MAX_SCALE - 2), 2).unwrap();
+        let price: Price = 1e40.into();
+        assert_eq!(price, highest);
+        assert!(price > Price::new(1, 0));
+        let price: Price = f32::MAX.into();
+        assert_eq!(price, highest);
+        let price: Price = f64::NAN.into();
+        assert_eq!(price, Price::new(0, 1));
+    }
+
+    #[test]
+    fn test_next_tick() {
+        assert_eq!(Price::new(10, 50).next_tick(), Some(Price::new(10, 51)));
+        assert_eq!(Price::new(10, 99).next_tick(), Some(Price::new(11, 0)));
+        assert_eq!(
+            Price::new(usize::MAX, 99).next_tick(),
+            Some(
+                Price::new(usize::MAX, 99)
+                    .rescale(2)
+                    .unwrap()
+                    .next_tick()
+                    .unwrap()
+            )
+        );
+        let highest = Price::from_units(u128::MAX / pow10(MAX_SCALE - 2), 2).unwrap();
+        assert_eq!(highest.next_tick(), None);
+    }
+
+    #[test]
+    fn test_prev_tick() {
+        assert_eq!(Price::new(10, 50).prev_tick(), Some(Price::new(10, 49)));
+        assert_eq!(Price::new(11, 0).prev_tick(), Some(Price::new(10, 99)));
+        assert_eq!(Price::new(0, 2).prev_tick(), Some(Price::new(0, 1)));
+        assert_eq!(Price::new(0, 1).prev_tick(), None);
     }
 
     #[test]
     fn test_price_ord() {
-        let price1 = Price {
-            main_unit: 10,
-            sub_unit: 50,
-        };
-        let price2 = Price {
-            main_unit: 10,
-            sub_unit: 75,
-        };
-        let price3 = Price {
-            main_unit: 12,
-            sub_unit: 0,
-        };
+        let price1 = Price::new(10, 50);
+        let price2 = Price::new(10, 75);
+        let price3 = Price::new(12, 0);
 
         assert!(price1 < price2);
         assert!(price2 > price1);
@@ -193,18 +578,9 @@ mod tests {
 
     #[test]
     fn test_price_partial_ord() {
-        let price1 = Price {
-            main_unit: 10,
-            sub_unit: 50,
-        };
-        let price2 = Price {
-            main_unit: 10,
-            sub_unit: 75,
-        };
-        let price3 = Price {
-            main_unit: 12,
-            sub_unit: 0,
-        };
+        let price1 = Price::new(10, 50);
+        let price2 = Price::new(10, 75);
+        let price3 = Price::new(12, 0);
 
         assert_eq!(price1.partial_cmp(&price2), Some(Ordering::Less));
         assert_eq!(price2.partial_cmp(&price1), Some(Ordering::Greater));
@@ -218,4 +594,163 @@ mod tests {
         assert_eq!(price3.partial_cmp(&price2), Some(Ordering::Greater));
         assert_eq!(price2.partial_cmp(&price3), Some(Ordering::Less));
     }
+
+    #[test]
+    fn test_display_zero_padded() {
+        assert_eq!(Price::new(10, 5).to_string(), "10.05");
+        assert_eq!(Price::new(10, 50).to_string(), "10.50");
+        assert_eq!(Price::new(0, 1).to_string(), "0.01");
+        assert_eq!(Price::from_units(1, 8).unwrap().to_string(), "0.00000001");
+        assert_eq!(Price::from_units(42, 0).unwrap().to_string(), "42");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("10.05".parse(), Ok(Price::new(10, 5)));
+        assert_eq!("0.01".parse(), Ok(Price::new(0, 1)));
+        assert_eq!("0.00000001".parse(), Price::from_units(1, 8));
+        assert_eq!("42".parse::<Price>().unwrap().get_scale(), 0);
+        // Trailing zeros are kept as decimal places
+        assert_eq!("10.50".parse::<Price>().unwrap().get_scale(), 2);
+    }
+
+    #[test]
+    fn test_from_str_rejected() {
+        for malformed in [
+            "", "1e2", "-1.00", "+1.00", " 1.00", "1.00 ", ".5", "5.", "1.2.3", "1,00", "NaN",
+        ] {
+            assert_eq!(
+                malformed.parse::<Price>(),
+                Err(PriceError::Malformed),
+                "{:?}",
+                malformed
+            );
+        }
+        assert_eq!("0.00".parse::<Price>(), Err(PriceError::Zero));
+        assert_eq!(
+            "0.0000000000000000001".parse::<Price>(),
+            Err(PriceError::ScaleTooLarge(19))
+        );
+        assert_eq!(
+            "1000000000000000000000000000000000000000".parse::<Price>(),
+            Err(PriceError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_checked_add_sub() {
+        assert_eq!(
+            Price::new(10, 50).checked_add(&Price::new(0, 75)),
+            Some(Price::new(11, 25))
+        );
+        // Result has the larger scale of both
+        let sum = Price::new(10, 0)
+            .checked_add(&Price::from_units(5, 3).unwrap())
+            .unwrap();
+        assert_eq!(sum.to_string(), "10.005");
+        assert_eq!(
+            Price::new(10, 5).checked_sub(&Price::new(10, 0)),
+            Some(Price::new(0, 5))
+        );
+        assert_eq!(Price::new(10, 0).checked_sub(&Price::new(10, 0)), None);
+        assert_eq!(Price::new(10, 0).checked_sub(&Price::new(11, 0)), None);
+    }
+
+    #[test]
+    fn test_checked_midpoint() {
+        assert_eq!(
+            Price::new(10, 0).checked_midpoint(&Price::new(10, 2)),
+            Some(Price::new(10, 1))
+        );
+        let midpoint = Price::new(10, 0)
+            .checked_midpoint(&Price::new(10, 1))
+            .unwrap();
+        assert_eq!(midpoint.to_string(), "10.005");
+        assert_eq!(
+            Price::from_units(1, MAX_SCALE)
+                .unwrap()
+                .checked_midpoint(&Price::from_units(2, MAX_SCALE).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_checked_ticks() {
+        let quarter = Price::new(0, 25);
+        assert_eq!(
+            Price::new(10, 0).checked_add_ticks(3, &quarter),
+            Some(Price::new(10, 75))
+        );
+        assert_eq!(
+            Price::new(10, 0).checked_sub_ticks(4, &quarter),
+            Some(Price::new(9, 0))
+        );
+        assert_eq!(Price::new(1, 0).checked_sub_ticks(4, &quarter), None);
+        assert_eq!(
+            Price::new(1, 0)
+                .checked_add_ticks(u64::MAX, &quarter)
+                .map(|p| p.get_scale()),
+            Some(2)
+        );
+        let highest = Price::from_units(u128::MAX, 18).unwrap();
+        assert_eq!(highest.checked_add_ticks(1, &Price::new(0, 1)), None);
+    }
+
+    #[test]
+    fn test_checked_mul_qty() {
+        let notional = Price::new(10, 5).checked_mul_qty(300).unwrap();
+        assert_eq!(notional.to_string(), "3015.00");
+        assert_eq!(notional, Notional::from_units(3015, 0));
+        assert_eq!(
+            notional.checked_add(&Price::new(0, 1).checked_mul_qty(1).unwrap()),
+            Some(Notional::from_units(301501, 2))
+        );
+        assert!(Notional::from_units(1, 0) > Notional::from_units(99, 2));
+        assert!(Notional::from_units(u128::MAX, 0) > Notional::from_units(1, 18));
+        assert_eq!(
+            Price::from_units(u128::MAX / pow10(MAX_SCALE), 18)
+                .unwrap()
+                .checked_mul_qty(u64::MAX),
+            Err(PriceError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(Price::new(10, 5).to_f64(), 10.05);
+        assert_eq!(Price::new(580, 37).to_f64(), 580.37);
+        assert_eq!(Price::from_units(1, 8).unwrap().to_f64(), 0.00000001);
+        assert_eq!(
+            Price::from_units(123_456_789_012_345_678_901, 18)
+                .unwrap()
+                .to_f64(),
+            "123.456789012345678901".parse::<f64>().unwrap()
+        );
+        assert_eq!(
+            Price::new(10, 5).checked_mul_qty(3).unwrap().to_f64(),
+            30.15
+        );
+    }
+
+    proptest! {
+        /// Formatting and parsing again yields the same value and scale
+        #[test]
+        fn test_display_from_str_round_trip(units in 1u128..=u64::MAX as u128, scale in 0..=MAX_SCALE) {
+            let price = Price::from_units(units, scale).unwrap();
+            let parsed: Price = price.to_string().parse().unwrap();
+            prop_assert_eq!(parsed.get_units(), units);
+            prop_assert_eq!(parsed.get_scale(), scale);
+        }
+
+        /// Parsing and formatting again yields the same string
+        #[test]
+        fn test_from_str_display_round_trip(main_unit in 1u64.., sub_unit in "[0-9]{0,18}") {
+            let text = if sub_unit.is_empty() {
+                main_unit.to_string()
+            } else {
+                format!("{}.{}", main_unit, sub_unit)
+            };
+            prop_assert_eq!(text.parse::<Price>().unwrap().to_string(), text);
+        }
+    }
 }
@@ -1 +1,2 @@
+pub mod event_listener;
 pub mod matching_engine;
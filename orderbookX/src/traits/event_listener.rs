@@ -0,0 +1,132 @@
+use std::sync::mpsc::Sender;
+
+use super::matching_engine::OrderType;
+use crate::{orderbook::LevelUpdate, price::Price};
+
+/// Listener for everything happening inside of the orderbook.
+/// Invoked synchronously by the orderbook in the order the events occur.
+///
+/// Defaults to ignoring all events.
+pub trait EventListener {
+    fn on_event(&mut self, _event: BookEvent) {}
+}
+
+/// Quantities are the visible quantities of the order.
+/// The hidden reserve of an iceberg order is only reported in the separate `hidden_qty` fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookEvent {
+    /// Incoming order has been accepted by the matching engine
+    OrderAccepted {
+        order_id: u64,
+        order_type: OrderType,
+        price: Price,
+        qty: u64,
+        hidden_qty: u64,
+    },
+    /// Order has been inserted into the orderbook as a maker order
+    OrderRested {
+        order_id: u64,
+        order_type: OrderType,
+        price: Price,
+        qty: u64,
+        hidden_qty: u64,
+    },
+    /// Stop order has been added to the trigger book
+    StopAccepted {
+        order_id: u64,
+        order_type: OrderType,
+        stop_price: Price,
+        qty: u64,
+        hidden_qty: u64,
+    },
+    /// Stop order has been triggered, followed by the events of the triggered order
+    StopTriggered {
+        order_id: u64,
+        order_type: OrderType,
+        stop_price: Price,
+    },
+    /// Order has been executed partially, the remaining quantity is still open
+    OrderPartiallyFilled {
+        order_id: u64,
+        order_type: OrderType,
+        counterparty_id: u64,
+        price: Price,
+        qty: u64,
+        remaining_qty: u64,
+        hidden_qty: u64,
+    },
+    /// Order has been executed completely
+    OrderFilled {
+        order_id: u64,
+        order_type: OrderType,
+        counterparty_id: u64,
+        price: Price,
+        qty: u64,
+    },
+    /// Iceberg order has shown the given quantity of its hidden reserve and moved to the back of its price level
+    OrderReplenished {
+        order_id: u64,
+        order_type: OrderType,
+        price: Price,
+        qty: u64,
+    },
+    /// Resting order has been modified to the given price and remaining quantity
+    OrderAmended {
+        order_id: u64,
+        order_type: OrderType,
+        price: Price,
+        qty: u64,
+        hidden_qty: u64,
+    },
+    /// Open quantity of the order has been cancelled
+    OrderCancelled {
+        order_id: u64,
+        order_type: OrderType,
+        qty: u64,
+        hidden_qty: u64,
+    },
+    /// New price level has been created
+    PriceLevelAdded { order_type: OrderType, price: Price },
+    /// Last order of a price level has been removed
+    PriceLevelRemoved { order_type: OrderType, price: Price },
+    /// Price level has been created, reduced, increased or removed.
+    /// Emitted after all other events of the change.
+    LevelUpdated(LevelUpdate),
+}
+
+/// Ignores all events
+#[derive(Default, Debug, Clone, Copy)]
+pub struct NoopListener;
+
+impl EventListener for NoopListener {}
+
+/// Collects all events, mostly useful for testing
+#[derive(Default, Debug, Clone)]
+pub struct EventCollector {
+    events: Vec<BookEvent>,
+}
+
+impl EventCollector {
+    pub fn get_events(&self) -> &[BookEvent] {
+        &self.events
+    }
+
+    /// Returns all collected events and clears the collector
+    pub fn take_events(&mut self) -> Vec<BookEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl EventListener for EventCollector {
+    fn on_event(&mut self, event: BookEvent) {
+        self.events.push(event);
+    }
+}
+
+/// Forwards all events into a channel.
+/// Events are dropped if the receiving side has hung up.
+impl EventListener for Sender<BookEvent> {
+    fn on_event(&mut self, event: BookEvent) {
+        let _ = self.send(event);
+    }
+}
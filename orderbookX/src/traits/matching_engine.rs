@@ -1,40 +1,154 @@
-use crate::orderbook::Order;
+use crate::{
+    error::BookError,
+    orderbook::{ExecutionReport, Order},
+    price::Price,
+};
 
 /// MatchingEngine providing the given order types.
-/// Iceberg orders or any form of hidden orders, stop loss orders/take profit orders, one cancels other (OCO) are not supported, as users can execute them independently using API access and bots.
+/// Iceberg orders only show their display quantity in the orderbook, see [IdentifiableOrder::with_display_qty](crate::orderbook::IdentifiableOrder::with_display_qty).
+///
+/// Every order returns an [ExecutionReport] listing its fills and what happened to the unfilled remainder.
+/// Invalid orders are rejected with a [BookError] before they touch the orderbook.
 pub trait MatchingEngine {
-    fn market_buy_until(&mut self, buy_order: Order) -> (bool, u64, u64, Order);
+    /// Single entry point for all orders.
+    /// Routes the order by its [OrderKind] and [TimeInForce] to the matching method below.
+    fn submit(&mut self, order: Order) -> Result<ExecutionReport, BookError>;
 
-    fn market_sell_until(&mut self, sell_order: Order) -> (bool, u64, u64, Order);
+    /// Market Order, the price of the order is ignored
+    fn market(&mut self, order: Order) -> Result<ExecutionReport, BookError>;
 
-    /// Market Buy
-    fn market_buy(&mut self, buy_order: Order) -> (bool, u64, u64);
-
-    /// Market sell
-    fn market_sell(&mut self, sell_order: Order) -> (bool, u64, u64);
+    /// Market Order until the price of the order is reached
+    fn market_until(&mut self, order: Order) -> Result<ExecutionReport, BookError>;
 
     /// Limit Order (Good till Cancel)
     /// A Good till Cancel (GTC) order is a buy or sell order that remains active until it is either filled or manually canceled by the trader.
     /// Unlike immediate execution orders, GTC orders can stay in the market for an extended period until they are executed or revoked by the trader.
-    fn match_and_insert(&mut self, order: Order, order_type: OrderType);
+    fn match_and_insert(&mut self, order: Order) -> Result<ExecutionReport, BookError>;
 
-    /// Limit or Cancel
-    fn limit_or_cancel_insert(&mut self, order: Order, order_type: OrderType);
+    /// Limit or Cancel (Post Only)
+    /// A Post Only order is a limit order that is only added to the orderbook as a maker order.
+    /// If any part of it would be executed immediately, it is rejected or repriced one tick behind the best opposite price, depending on the [PostOnlyMode].
+    /// Does not allow any execution
+    fn limit_or_cancel_insert(
+        &mut self,
+        order: Order,
+        mode: PostOnlyMode,
+    ) -> Result<(PostOnlyOutcome, ExecutionReport), BookError>;
 
     /// Limit Order (Immediate or Cancel)
     /// An Immediate-Or-Cancel (IOC) order is a buy or sell order that requires immediate execution.
     /// If an IOC order cannot be completed instantly, the remaining unexecuted amount will be automatically canceled.
     /// Allows partial execution
-    fn immediate_or_cancel_insert(&mut self, order: Order, order_type: OrderType);
+    fn immediate_or_cancel_insert(&mut self, order: Order) -> Result<ExecutionReport, BookError>;
 
     /// Limit Order (Fill or Kill)
     /// A Fill or Kill (FOK) order is a buy or sell order that must be executed in its entirety immediately.
     /// If the order cannot be filled completely at once, it will be canceled instead of being partially executed.
     /// Does not allow partial execution
-    fn fill_or_kill_insert(&mut self, order: Order, order_type: OrderType);
+    fn fill_or_kill_insert(&mut self, order: Order) -> Result<ExecutionReport, BookError>;
+
+    /// Stop Order (Stop Market or Stop Limit)
+    /// A stop order is held back until the last traded price reaches its stop price.
+    /// Buy stops are triggered at or above, sell stops at or below their stop price.
+    /// Once triggered it is executed as a market or limit order with its time in force.
+    fn stop_insert(&mut self, order: Order) -> Result<ExecutionReport, BookError>;
+
+    /// One Cancels Other (OCO)
+    /// Two linked orders, once one of them is executed the other one is cancelled within the same fill.
+    /// Each order is submitted like a single order, e.g. a take profit limit order and a stop loss stop order.
+    fn one_cancels_other_insert(
+        &mut self,
+        first: Order,
+        second: Order,
+        trigger: OcoTrigger,
+    ) -> Result<(ExecutionReport, ExecutionReport), BookError>;
+
+    /// Bracket Order
+    /// An entry order with a take profit and a stop loss order on the opposite side.
+    /// The exit orders are only submitted as an OCO pair once the entry order has been filled completely.
+    fn bracket_insert(
+        &mut self,
+        entry: Order,
+        take_profit: Order,
+        stop_loss: Order,
+    ) -> Result<ExecutionReport, BookError>;
 }
 
+/// Side of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OrderType {
     Buy,
     Sell,
 }
+
+impl OrderType {
+    /// Returns the side an order of this type is matched against
+    pub fn opposite(&self) -> Self {
+        match self {
+            OrderType::Buy => OrderType::Sell,
+            OrderType::Sell => OrderType::Buy,
+        }
+    }
+}
+
+/// How an order is executed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    /// Executed up to its limit price
+    #[default]
+    Limit,
+    /// Executed at any price, the price of the order is ignored.
+    /// The unfilled remainder is always cancelled.
+    Market,
+    /// Market order once the stop price is reached
+    StopMarket,
+    /// Limit order once the stop price is reached
+    StopLimit,
+}
+
+/// How long a limit order stays active
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeInForce {
+    /// Rests in the orderbook until it is filled or cancelled
+    #[default]
+    GoodTillCancel,
+    /// Unfilled remainder is cancelled immediately
+    ImmediateOrCancel,
+    /// Executed completely or not at all
+    FillOrKill,
+    /// Only added to the orderbook as a maker order
+    PostOnly(PostOnlyMode),
+    /// Rests in the orderbook until the given timestamp in nanoseconds since the Unix epoch
+    GoodTillDate(u64),
+    /// Rests in the orderbook until the end of the trading session
+    Day,
+}
+
+/// Behavior of a post only order that would cross the spread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostOnlyMode {
+    /// Reject the whole order
+    Reject,
+    /// Reprice the order one tick behind the best opposite price
+    Slide,
+}
+
+/// Execution of one order of a one cancels other pair which cancels the other order
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OcoTrigger {
+    /// Complete fill
+    #[default]
+    Fill,
+    /// Any fill, including partial fills
+    PartialFill,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostOnlyOutcome {
+    /// Inserted at the requested price
+    Accepted,
+    /// Not inserted, as it would have crossed the spread
+    Rejected,
+    /// Inserted at the given price instead of the requested price
+    Repriced(Price),
+}